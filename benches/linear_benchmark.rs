@@ -4,7 +4,7 @@ use svm_burns::kernel::LinearKernel;
 
 fn linear_kernel_benchmark(c: &mut Criterion) {
     let svm_burns_kernel = LinearKernel::default();
-    let smartcore_kernel = SmartcoreLinear::default();
+    let smartcore_kernel = SmartcoreLinear;
 
     let x = vec![1.0, 2.0, 3.0];
     let y = vec![4.0, 5.0, 6.0];
@@ -38,7 +38,7 @@ fn svm_burns_benchmark(c: &mut Criterion) {
 
     c.bench_function("svm_burns", |b| {
         b.iter(|| {
-            svm_burns::svm::SVM::fit(&mut svc, black_box(&x), black_box(&y));
+            svm_burns::svm::SVM::fit(&mut svc, black_box(&x), black_box(&y)).unwrap();
         })
     });
 }
@@ -1,31 +1,187 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    mem,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::float::Float;
+
+/// Default memory budget of a [`Cache`], 100 MiB like libsvm.
+pub const DEFAULT_CACHE_SIZE: usize = 100 * 1024 * 1024;
+
+/// Bounded kernel cache with least recently used eviction of rows.
+///
+/// The values `K(i, j)` are stored at column `j` of row `i`, since the kernel is symmetric
+/// a lookup of `(i, j)` also finds a value stored as `(j, i)`. A row is a contiguous
+/// vector that grows up to the largest column stored in it, columns that were not
+/// computed yet hold NaN. A row is charged its values plus a fixed overhead for its
+/// bookkeeping.
+#[derive(Debug)]
+pub struct Cache<T = f64> {
+    rows: HashMap<usize, Row<T>>,
+    /// rows by the time of their last use
+    lru: BTreeMap<u64, usize>,
+    tick: u64,
+    /// memory budget in bytes
+    capacity: usize,
+    size: usize,
+    stats: CacheStats,
+}
 
 #[derive(Debug)]
-pub struct Cache {
-    data: HashMap<(usize, usize), f64>,
+struct Row<T> {
+    values: Vec<T>,
+    last_used: u64,
+}
+
+/// Lookups of a [`Cache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    /// evicted rows
+    pub evictions: usize,
+}
+
+impl<T: Float> Default for Cache<T> {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CACHE_SIZE)
+    }
 }
 
-impl Cache {
+impl<T: Float> Cache<T> {
     pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cache that keeps at most `capacity` bytes of kernel values.
+    pub fn with_capacity(capacity: usize) -> Self {
         Cache {
-            data: HashMap::new(),
+            rows: HashMap::new(),
+            lru: BTreeMap::new(),
+            // new rows have not been used at tick 0
+            tick: 1,
+            capacity,
+            size: 0,
+            stats: CacheStats::default(),
         }
     }
 
-    fn get(&self, i: usize, j: usize) -> Option<&f64> {
-        self.data.get(&(i, j))
+    /// Bytes of a row beside its values, its slots in the row map and in the LRU map.
+    const ROW_OVERHEAD: usize = mem::size_of::<(usize, Row<T>)>() + mem::size_of::<(u64, usize)>();
+
+    fn row_size(len: usize) -> usize {
+        Self::ROW_OVERHEAD + len * mem::size_of::<T>()
+    }
+
+    /// Bytes of the stored rows including their overhead.
+    pub fn size(&self) -> usize {
+        self.size
     }
 
-    pub fn insert(&mut self, key: (usize, usize), value: f64) {
-        self.data.insert(key, value);
+    pub fn stats(&self) -> CacheStats {
+        self.stats
     }
 
-    pub fn get_or_insert(&mut self, key: (usize, usize), value: f64) -> &f64 {
-        self.data.entry(key).or_insert(value)
+    pub fn get(&mut self, i: usize, j: usize) -> Option<&T> {
+        let row = if self.contains(i, j) {
+            i
+        } else if self.contains(j, i) {
+            j
+        } else {
+            self.stats.misses += 1;
+            return None;
+        };
+        self.stats.hits += 1;
+        self.touch(row);
+        let column = if row == i { j } else { i };
+        Some(&self.rows[&row].values[column])
+    }
+
+    /// Stores `K(i, j)` in row `i`, least recently used rows are evicted until the grown
+    /// row fits. A row that does not fit into the cache on its own is not stored.
+    pub fn insert(&mut self, (i, j): (usize, usize), value: T) {
+        if self.contains(i, j) || self.contains(j, i) {
+            return;
+        }
+        let len = self.rows.get(&i).map(|row| row.values.len());
+        let new_len = len.unwrap_or(0).max(j + 1);
+        if Self::row_size(new_len) > self.capacity {
+            return;
+        }
+        let growth = Self::row_size(new_len) - len.map_or(0, Self::row_size);
+        while self.size + growth > self.capacity && self.evict_except(i) {}
+
+        let row = self.rows.entry(i).or_insert_with(|| Row {
+            values: Vec::new(),
+            last_used: 0,
+        });
+        if row.values.len() < new_len {
+            row.values.resize(new_len, T::nan());
+        }
+        row.values[j] = value;
+        self.size += growth;
+        self.touch(i);
     }
 
+    /// Drops the rows of the given indices.
     pub fn drop_all(&mut self, idxs: HashSet<usize>) {
-        self.data.retain(|k, _| !idxs.contains(&k.0));
+        for i in idxs {
+            if let Some(row) = self.rows.remove(&i) {
+                self.lru.remove(&row.last_used);
+                self.size -= Self::row_size(row.values.len());
+            }
+        }
+    }
+
+    fn contains(&self, i: usize, j: usize) -> bool {
+        self.rows
+            .get(&i)
+            .and_then(|row| row.values.get(j))
+            .is_some_and(|value| !value.is_nan())
+    }
+
+    /// Marks row `i` as the most recently used one.
+    fn touch(&mut self, i: usize) {
+        let Some(row) = self.rows.get_mut(&i) else {
+            return;
+        };
+        if row.last_used == self.tick {
+            return;
+        }
+        self.lru.remove(&row.last_used);
+        self.tick += 1;
+        row.last_used = self.tick;
+        self.lru.insert(self.tick, i);
+    }
+
+    /// Evicts the least recently used row other than `keep`, returns `false` if there is
+    /// none.
+    fn evict_except(&mut self, keep: usize) -> bool {
+        let Some((&tick, &i)) = self.lru.iter().find(|(_, &i)| i != keep) else {
+            return false;
+        };
+        self.lru.remove(&tick);
+        if let Some(row) = self.rows.remove(&i) {
+            self.size -= Self::row_size(row.values.len());
+        }
+        self.stats.evictions += 1;
+        true
+    }
+}
+
+impl<T: Float> Cache<T> {
+    /// Returns the cached `K(i, j)` or computes and stores it.
+    pub fn get_or_compute(&mut self, i: usize, j: usize, compute: impl FnOnce() -> T) -> T {
+        match self.get(i, j) {
+            Some(&value) => value,
+            None => {
+                let value = compute();
+                self.insert((i, j), value);
+                value
+            }
+        }
     }
 }
 
@@ -39,13 +195,56 @@ mod tests {
 
         cache.insert((0, 0), 1.0);
         cache.insert((0, 1), 2.0);
-        cache.insert((1, 0), 3.0);
         cache.insert((1, 1), 4.0);
 
         assert_eq!(cache.get(0, 0), Some(&1.0));
         assert_eq!(cache.get(0, 1), Some(&2.0));
-        assert_eq!(cache.get(1, 0), Some(&3.0));
+        assert_eq!(cache.get(1, 0), Some(&2.0));
         assert_eq!(cache.get(1, 1), Some(&4.0));
         assert_eq!(cache.get(2, 2), None);
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 4,
+                misses: 1,
+                evictions: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_cache_eviction() {
+        let row = Cache::<f64>::row_size;
+        let mut cache = Cache::with_capacity(2 * row(3));
+
+        cache.insert((0, 1), 1.0);
+        cache.insert((0, 2), 2.0);
+        cache.insert((1, 2), 3.0);
+        assert_eq!(cache.size(), 2 * row(3));
+        // column 0 of row 0 was not computed
+        assert_eq!(cache.get(0, 0), None);
+
+        // row 1 is the least recently used one after reading row 0
+        assert_eq!(cache.get(2, 0), Some(&2.0));
+        cache.insert((3, 0), 4.0);
+        assert_eq!(cache.get(1, 2), None);
+        assert_eq!(cache.get(0, 1), Some(&1.0));
+        assert_eq!(cache.stats().evictions, 1);
+        assert_eq!(cache.size(), row(3) + row(1));
+
+        // growing a row is charged for the new values
+        cache.insert((3, 2), 5.0);
+        assert_eq!(cache.size(), 2 * row(3));
+        assert_eq!(cache.stats().evictions, 1);
+
+        assert_eq!(cache.get_or_compute(3, 0, || unreachable!()), 4.0);
+        assert_eq!(cache.get_or_compute(5, 6, || 7.0), 7.0);
+        assert!(cache.size() <= 2 * row(3));
+
+        // a row larger than the cache is not stored and evicts nothing
+        let evictions = cache.stats().evictions;
+        cache.insert((0, 100), 8.0);
+        assert_eq!(cache.get(0, 100), None);
+        assert_eq!(cache.stats().evictions, evictions);
     }
 }
@@ -0,0 +1,221 @@
+//! Datasets in the sparse text format of libsvm and svmlight.
+//!
+//! Each line holds the label of a sample followed by its non-zero features as
+//! `index:value` pairs with increasing 1-based indices, text after `#` is a comment:
+//!
+//! ```text
+//! 1 1:0.5 3:1.25
+//! -1 2:1 # a comment
+//! ```
+//!
+//! The query id of svmlight's ranking format, `qid:N` after the label, is checked and
+//! skipped. Other svmlight extensions are not supported: `sid:` slack ids and `cost:`
+//! factors are rejected, and the label 0 of unlabeled samples for transduction is read
+//! as the class 0.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    error::{Error, Result},
+    float::Float,
+    libsvm,
+    matrix::Matrix,
+    sparse::{CsrMatrix, SparseVector},
+    X, Y,
+};
+
+/// Samples and their labels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dataset<T: Float = f64> {
+    pub x: CsrMatrix<T>,
+    pub y: Y,
+}
+
+impl<T: Float> Dataset<T> {
+    pub fn new(x: CsrMatrix<T>, y: Y) -> Result<Self> {
+        if x.n_rows() != y.len() {
+            return Err(Error::DimensionMismatch(format!(
+                "{} samples but {} labels",
+                x.n_rows(),
+                y.len()
+            )));
+        }
+        Ok(Dataset { x, y })
+    }
+
+    /// Keeps the non-zero features of dense samples.
+    pub fn from_dense(x: &(impl Matrix<T> + ?Sized), y: Y) -> Result<Self> {
+        Self::new(CsrMatrix::from_dense(x), y)
+    }
+
+    /// Reads a dataset, the number of features is the largest index.
+    pub fn read(reader: impl BufRead) -> Result<Self> {
+        let mut rows = Vec::new();
+        let mut y = Vec::new();
+        let mut n_features = 0;
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let error = |message: String| Error::Parse {
+                line: number + 1,
+                message,
+            };
+            let content = line.split('#').next().unwrap_or_default();
+            let mut tokens = content.split_whitespace().peekable();
+            let Some(label) = tokens.next() else {
+                continue;
+            };
+            y.push(parse_label(label).map_err(error)?);
+            if let Some(qid) = tokens.next_if(|token| token.starts_with("qid:")) {
+                if qid["qid:".len()..].parse::<u64>().is_err() {
+                    return Err(error(format!("invalid query id \"{qid}\"")));
+                }
+            }
+            let row: SparseVector<T> = libsvm::parse_features(tokens).map_err(error)?;
+            if let Some(&index) = row.as_row().indices.last() {
+                n_features = n_features.max(index + 1);
+            }
+            rows.push(row);
+        }
+        Self::new(CsrMatrix::from_rows(n_features, &rows)?, y)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Widens the samples to `n_features`, e.g. the features of the training set for a
+    /// test set whose last features are all zero.
+    pub fn with_n_features(self, n_features: usize) -> Result<Self> {
+        let x = CsrMatrix::new(
+            n_features,
+            self.x.indptr().to_vec(),
+            self.x.indices().to_vec(),
+            self.x.values().to_vec(),
+        )?;
+        Ok(Dataset { x, y: self.y })
+    }
+
+    pub fn n_features(&self) -> usize {
+        self.x.n_cols()
+    }
+
+    /// Dense copy of the samples.
+    pub fn to_dense(&self) -> X<T> {
+        (0..self.x.n_rows())
+            .map(|i| self.x.row(i).to_dense(self.x.n_cols()))
+            .collect()
+    }
+
+    /// Writes the dataset in the format read by [`Dataset::read`].
+    pub fn write(&self, mut writer: impl Write) -> Result<()> {
+        for (i, label) in self.y.iter().enumerate() {
+            write!(writer, "{label}")?;
+            let row = self.x.row(i);
+            for (index, value) in row.indices.iter().zip(row.values) {
+                if *value != T::zero() {
+                    write!(writer, " {}:{value}", index + 1)?;
+                }
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Parses integer labels, also written as `+1` or `1.0`.
+fn parse_label(token: &str) -> std::result::Result<i32, String> {
+    if let Ok(label) = token.parse() {
+        return Ok(label);
+    }
+    match token.parse::<f64>() {
+        Ok(label) if label.fract() == 0.0 && label.abs() <= i32::MAX as f64 => Ok(label as i32),
+        _ => Err(format!("invalid label \"{token}\", expected an integer")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempFile;
+
+    const DATA: &str = "# training data
++1 1:0.5 3:1.25
+-1 2:1 # a comment
+
+1.0 1:-2
+";
+
+    #[test]
+    fn read_dataset() {
+        let dataset = Dataset::<f64>::read(DATA.as_bytes()).unwrap();
+        assert_eq!(dataset.y, vec![1, -1, 1]);
+        assert_eq!(dataset.n_features(), 3);
+        assert_eq!(
+            dataset.to_dense(),
+            vec![
+                vec![0.5, 0.0, 1.25],
+                vec![0.0, 1.0, 0.0],
+                vec![-2.0, 0.0, 0.0]
+            ]
+        );
+        assert_eq!(dataset.clone().with_n_features(5).unwrap().n_features(), 5);
+        assert!(dataset.clone().with_n_features(2).is_err());
+
+        let mut text = Vec::new();
+        dataset.write(&mut text).unwrap();
+        assert_eq!(
+            String::from_utf8(text.clone()).unwrap(),
+            "1 1:0.5 3:1.25\n-1 2:1\n1 1:-2\n"
+        );
+        assert_eq!(Dataset::read(text.as_slice()).unwrap(), dataset);
+
+        let ranking = "2 qid:1 1:0.5 3:1.25\n1 qid:1 2:1\n";
+        let dataset = Dataset::<f64>::read(ranking.as_bytes()).unwrap();
+        assert_eq!(dataset.y, vec![2, 1]);
+        assert_eq!(dataset.to_dense()[1], vec![0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn read_invalid_dataset() {
+        let error = |text: &str| match Dataset::<f64>::read(text.as_bytes()) {
+            Err(Error::Parse { line, message }) => (line, message),
+            other => panic!("expected a parse error, got {other:?}"),
+        };
+        assert_eq!(error("1 1:1\n\nx 1:1").0, 3);
+        assert_eq!(error("1 1:1\n-1 2:1 1:1").0, 2);
+        assert_eq!(
+            error("1 0:1"),
+            (1, "feature indices start at 1".to_string())
+        );
+        assert!(error("1 1:a").1.contains("invalid value"));
+        assert!(error("1 1").1.contains("index:value"));
+        assert!(error("1 qid:a 1:1").1.contains("query id"));
+        assert!(error("1 1:1 qid:2").1.contains("invalid value"));
+        assert!(error("1 sid:3 1:1").1.contains("invalid value"));
+    }
+
+    #[test]
+    fn load_save_dataset() {
+        let x: Vec<Vec<f32>> = vec![vec![1.0, 0.0], vec![0.0, -1.5]];
+        let dataset = Dataset::<f32>::from_dense(&x, vec![3, 7]).unwrap();
+        let file = TempFile::new("dataset.svm");
+        dataset.save(file.path()).unwrap();
+        assert_eq!(Dataset::<f32>::load(file.path()).unwrap(), dataset);
+        let missing = TempFile::new("dataset_missing.svm");
+        assert!(matches!(
+            Dataset::<f32>::load(missing.path()),
+            Err(Error::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use crate::kernel::KernelError;
+
+/// Errors returned by training and prediction.
+#[derive(Debug)]
+pub enum Error {
+    /// the model has to be trained before it can predict
+    NotFitted,
+    /// the shape of the input does not match the labels or the trained model
+    DimensionMismatch(String),
+    /// the labels can not be used by the estimator
+    InvalidLabel(String),
+    /// a parameter is out of its valid range
+    InvalidParameter(String),
+    /// no samples were given
+    EmptyInput,
+    /// the kernel could not be rebuilt
+    Kernel(KernelError),
+    /// reading or writing a model or dataset failed
+    Io(std::io::Error),
+    /// a model file is corrupted or was written by an incompatible version
+    Format(String),
+    /// a line of a dataset file can not be parsed, lines are counted from 1
+    Parse { line: usize, message: String },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFitted => write!(f, "model is not trained"),
+            Error::DimensionMismatch(message) => write!(f, "dimension mismatch: {message}"),
+            Error::InvalidLabel(message) => write!(f, "invalid label: {message}"),
+            Error::InvalidParameter(message) => write!(f, "invalid parameter: {message}"),
+            Error::EmptyInput => write!(f, "input contains no samples"),
+            Error::Kernel(err) => write!(f, "{err}"),
+            Error::Io(err) => write!(f, "io error: {err}"),
+            Error::Format(message) => write!(f, "invalid model file: {message}"),
+            Error::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Kernel(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<KernelError> for Error {
+    fn from(err: KernelError) -> Self {
+        Error::Kernel(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
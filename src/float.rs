@@ -0,0 +1,53 @@
+use std::{
+    fmt::{Debug, Display},
+    iter::Sum,
+    ops::{AddAssign, DivAssign, MulAssign, SubAssign},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Floating point type of the samples, kernel values and models, `f32` or `f64`.
+///
+/// Hyperparameters in [`Parameters`](crate::Parameters) stay `f64` and are converted
+/// with [`Float::cast`].
+pub trait Float:
+    num_traits::Float
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Default
+    + Debug
+    + Display
+    + Serialize
+    + DeserializeOwned
+    + Sync
+    + Send
+    + 'static
+{
+    /// Converts from `f64`, rounding to the nearest representable value.
+    fn cast(value: f64) -> Self;
+
+    fn as_f64(self) -> f64;
+}
+
+impl Float for f32 {
+    fn cast(value: f64) -> Self {
+        value as f32
+    }
+
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Float for f64 {
+    fn cast(value: f64) -> Self {
+        value
+    }
+
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
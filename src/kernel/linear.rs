@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::Kernel;
+use crate::{
+    float::Float,
+    sparse::{self, SparseRow},
+    Kernel,
+};
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct LinearKernel {}
@@ -11,11 +15,15 @@ impl LinearKernel {
     }
 }
 
-impl Kernel for LinearKernel {
-    fn compute(&self, x: &Vec<f64>, y: &Vec<f64>) -> f64 {
+impl<T: Float> Kernel<T> for LinearKernel {
+    fn compute(&self, x: &[T], y: &[T]) -> T {
         x.iter().zip(y.iter()).map(|(&xi, &yi)| xi * yi).sum()
     }
 
+    fn compute_sparse(&self, x: SparseRow<T>, y: SparseRow<T>) -> T {
+        sparse::dot(x, y)
+    }
+
     fn type_of(&self) -> super::KernelType {
         super::KernelType::Linear
     }
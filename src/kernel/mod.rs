@@ -1,16 +1,40 @@
 // sources: https://github.com/smartcorelib/smartcore/blob/development/src/svm/mod.rs
 
 pub mod linear;
+pub mod polynomial;
 pub mod rbf;
+pub mod registry;
+pub mod sigmoid;
+pub mod source;
 
 pub use linear::LinearKernel;
+pub use polynomial::PolynomialKernel;
 pub use rbf::RBFKernel;
+pub use registry::{register_kernel, KernelError};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+use crate::{float::Float, sparse::SparseRow};
+pub use sigmoid::SigmoidKernel;
+pub use source::{FeatureKernelSource, KernelSource, PrecomputedKernelSource, SparseKernelSource};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum KernelType {
     Linear,
     RBF(f64),
+    Polynomial {
+        degree: u32,
+        gamma: f64,
+        coef0: f64,
+    },
+    Sigmoid {
+        gamma: f64,
+        coef0: f64,
+    },
+    /// kernel defined outside of this crate, rebuilt through the [`registry`]
+    Custom {
+        name: String,
+        parameters: serde_json::Value,
+    },
 }
 
 impl KernelType {
@@ -22,19 +46,79 @@ impl KernelType {
         Box::new(RBFKernel::default())
     }
 
-    pub fn new(&self) -> Box<dyn Kernel> {
-        match self {
-            KernelType::Linear => KernelType::linear(),
+    pub fn polynomial() -> Box<dyn Kernel> {
+        Box::new(PolynomialKernel::default())
+    }
+
+    pub fn sigmoid() -> Box<dyn Kernel> {
+        Box::new(SigmoidKernel::default())
+    }
+
+    /// Describes a custom kernel by its registered name and its serialized state, fails if
+    /// the kernel can not be serialized.
+    pub fn custom<K: Serialize>(name: &str, kernel: &K) -> Result<KernelType, KernelError> {
+        let parameters =
+            serde_json::to_value(kernel).map_err(|source| KernelError::InvalidParameters {
+                name: name.to_string(),
+                source,
+            })?;
+        Ok(KernelType::Custom {
+            name: name.to_string(),
+            parameters,
+        })
+    }
+
+    /// Builds the kernel described by this type for the float type `T`.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<T: Float>(&self) -> Result<Box<dyn Kernel<T>>, KernelError> {
+        let kernel: Box<dyn Kernel<T>> = match self {
+            KernelType::Linear => Box::new(LinearKernel::default()),
             KernelType::RBF(gamma) => Box::new(RBFKernel::new(*gamma)),
-        }
+            KernelType::Polynomial {
+                degree,
+                gamma,
+                coef0,
+            } => Box::new(PolynomialKernel::new(*degree, *gamma, *coef0)),
+            KernelType::Sigmoid { gamma, coef0 } => Box::new(SigmoidKernel::new(*gamma, *coef0)),
+            KernelType::Custom { name, parameters } => {
+                registry::build_kernel_as(name, parameters.clone())?
+            }
+        };
+        Ok(kernel)
     }
 }
 
-pub trait Kernel: Sync + Send {
-    fn compute(&self, x: &Vec<f64>, y: &Vec<f64>) -> f64;
+pub trait Kernel<T: Float = f64>: Sync + Send {
+    fn compute(&self, x: &[T], y: &[T]) -> T;
+
+    /// Kernel value between two sparse vectors. The default densifies both vectors,
+    /// the built-in kernels only visit the non-zero entries.
+    fn compute_sparse(&self, x: SparseRow<T>, y: SparseRow<T>) -> T {
+        let n = x
+            .indices
+            .last()
+            .max(y.indices.last())
+            .map_or(0, |&index| index + 1);
+        self.compute(&x.to_dense(n), &y.to_dense(n))
+    }
+
     fn type_of(&self) -> KernelType;
 }
 
+impl<T: Float, K: Kernel<T> + ?Sized> Kernel<T> for Box<K> {
+    fn compute(&self, x: &[T], y: &[T]) -> T {
+        (**self).compute(x, y)
+    }
+
+    fn compute_sparse(&self, x: SparseRow<T>, y: SparseRow<T>) -> T {
+        (**self).compute_sparse(x, y)
+    }
+
+    fn type_of(&self) -> KernelType {
+        (**self).type_of()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,7 +134,7 @@ mod tests {
 
     #[test]
     fn rbf_kernel() {
-        let v1 = vec![1., 2., 3.];
+        let v1: Vec<f64> = vec![1., 2., 3.];
         let v2 = vec![4., 5., 6.];
 
         let result = RBFKernel::default()
@@ -60,4 +144,24 @@ mod tests {
 
         assert!((0.2265f64 - result) < 1e-4);
     }
+
+    #[test]
+    fn sparse_kernels_match_dense() {
+        let x = [0.0, 1.5, 0.0, -2.0, 0.5];
+        let y = [1.0, 0.0, 0.0, 3.0, 0.0];
+        let x_sparse = crate::sparse::SparseVector::from_dense(&x);
+        let y_sparse = crate::sparse::SparseVector::from_dense(&y);
+
+        let kernels: Vec<Box<dyn Kernel>> = vec![
+            KernelType::linear(),
+            Box::new(RBFKernel::new(0.3)),
+            Box::new(PolynomialKernel::new(2, 0.5, 1.0)),
+            Box::new(SigmoidKernel::new(0.1, 0.0)),
+        ];
+        for kernel in kernels {
+            let dense = kernel.compute(&x, &y);
+            let sparse = kernel.compute_sparse(x_sparse.as_row(), y_sparse.as_row());
+            assert!((dense - sparse).abs() < 1e-12, "{dense} != {sparse}");
+        }
+    }
 }
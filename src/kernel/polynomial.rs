@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    float::Float,
+    sparse::{self, SparseRow},
+    Kernel,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct PolynomialKernel {
+    degree: u32,
+    gamma: f64,
+    coef0: f64,
+}
+
+impl Default for PolynomialKernel {
+    fn default() -> Self {
+        PolynomialKernel {
+            degree: 3,
+            gamma: 1.0,
+            coef0: 1.0,
+        }
+    }
+}
+
+impl PolynomialKernel {
+    pub fn new(degree: u32, gamma: f64, coef0: f64) -> PolynomialKernel {
+        PolynomialKernel {
+            degree,
+            gamma,
+            coef0,
+        }
+    }
+
+    pub fn with_degree(mut self, degree: u32) -> PolynomialKernel {
+        self.degree = degree;
+        self
+    }
+
+    pub fn with_gamma(mut self, gamma: f64) -> PolynomialKernel {
+        self.gamma = gamma;
+        self
+    }
+
+    pub fn with_coef0(mut self, coef0: f64) -> PolynomialKernel {
+        self.coef0 = coef0;
+        self
+    }
+
+    /// `(gamma * dot + coef0)^degree`, an integer power is defined for negative bases.
+    fn power<T: Float>(&self, dot: T) -> T {
+        let degree = i32::try_from(self.degree).unwrap_or(i32::MAX);
+        (T::cast(self.gamma) * dot + T::cast(self.coef0)).powi(degree)
+    }
+}
+
+impl<T: Float> Kernel<T> for PolynomialKernel {
+    fn compute(&self, x: &[T], y: &[T]) -> T {
+        let dot: T = x.iter().zip(y.iter()).map(|(&xi, &yi)| xi * yi).sum();
+        self.power(dot)
+    }
+
+    fn compute_sparse(&self, x: SparseRow<T>, y: SparseRow<T>) -> T {
+        self.power(sparse::dot(x, y))
+    }
+
+    fn type_of(&self) -> super::KernelType {
+        super::KernelType::Polynomial {
+            degree: self.degree,
+            gamma: self.gamma,
+            coef0: self.coef0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polynomial_kernel() {
+        let v1 = vec![1., 2., 3.];
+        let v2 = vec![4., 5., 6.];
+
+        let result = PolynomialKernel::default()
+            .with_degree(3)
+            .with_gamma(0.5)
+            .with_coef0(1.0)
+            .compute(&v1, &v2);
+
+        assert_eq!(4913.0, result);
+    }
+
+    #[test]
+    fn polynomial_kernel_negative_base() {
+        let kernel = PolynomialKernel::new(3, 1.0, 0.0);
+        assert_eq!(kernel.compute(&[-1.0], &[2.0]), -8.0);
+        assert_eq!(kernel.with_degree(2).compute(&[-1.0], &[2.0]), 4.0);
+    }
+}
@@ -1,8 +1,10 @@
-use std::ops::Mul;
-
 use serde::{Deserialize, Serialize};
 
-use crate::Kernel;
+use crate::{
+    float::Float,
+    sparse::{self, SparseRow},
+    Kernel,
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct RBFKernel {
@@ -26,16 +28,20 @@ impl RBFKernel {
     }
 }
 
-impl Kernel for RBFKernel {
-    fn compute(&self, x: &Vec<f64>, y: &Vec<f64>) -> f64 {
+impl<T: Float> Kernel<T> for RBFKernel {
+    fn compute(&self, x: &[T], y: &[T]) -> T {
         x.iter()
             .zip(y.iter())
             .map(|(&xi, &yi)| (xi - yi).powi(2))
-            .sum::<f64>()
-            .mul(-self.gamma)
+            .sum::<T>()
+            .mul(-T::cast(self.gamma))
             .exp()
     }
 
+    fn compute_sparse(&self, x: SparseRow<T>, y: SparseRow<T>) -> T {
+        (-T::cast(self.gamma) * sparse::squared_distance(x, y)).exp()
+    }
+
     fn type_of(&self) -> super::KernelType {
         super::KernelType::RBF(self.gamma)
     }
@@ -47,7 +53,7 @@ mod tests {
 
     #[test]
     fn rbf_kernel() {
-        let v1 = vec![1., 2., 3.];
+        let v1: Vec<f64> = vec![1., 2., 3.];
         let v2 = vec![4., 5., 6.];
 
         let result = RBFKernel::default()
@@ -0,0 +1,263 @@
+//! Registry of user-defined kernels.
+//!
+//! Built-in kernels are rebuilt from their [`KernelType`](super::KernelType) variant.
+//! Kernels defined outside of this crate serialize as `KernelType::Custom` and are
+//! rebuilt by looking up their name in this registry.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    sync::{OnceLock, RwLock},
+};
+
+use serde_json::Value;
+
+use super::{Kernel, KernelType};
+use crate::{float::Float, sparse::SparseRow};
+
+/// Builds a kernel from the parameters stored in `KernelType::Custom`.
+pub type KernelConstructor = fn(Value) -> serde_json::Result<Box<dyn Kernel>>;
+
+fn registry() -> &'static RwLock<HashMap<String, KernelConstructor>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, KernelConstructor>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Registers a constructor for the custom kernel `name`.
+///
+/// Registering a name twice replaces the previous constructor.
+pub fn register_kernel(name: &str, constructor: KernelConstructor) {
+    registry()
+        .write()
+        .expect("kernel registry poisoned")
+        .insert(name.to_string(), constructor);
+}
+
+/// Returns true if a constructor is registered for `name`.
+pub fn is_registered(name: &str) -> bool {
+    registry()
+        .read()
+        .expect("kernel registry poisoned")
+        .contains_key(name)
+}
+
+/// Builds the custom kernel `name` from its serialized parameters.
+pub fn build_kernel(name: &str, parameters: Value) -> Result<Box<dyn Kernel>, KernelError> {
+    let constructor = registry()
+        .read()
+        .expect("kernel registry poisoned")
+        .get(name)
+        .copied()
+        .ok_or_else(|| KernelError::UnknownKernel(name.to_string()))?;
+    constructor(parameters).map_err(|source| KernelError::InvalidParameters {
+        name: name.to_string(),
+        source,
+    })
+}
+
+/// Builds the custom kernel `name` for the float type `T`.
+///
+/// Registered constructors build `f64` kernels, for other float types the samples are
+/// converted to `f64` on every evaluation.
+pub(crate) fn build_kernel_as<T: Float>(
+    name: &str,
+    parameters: Value,
+) -> Result<Box<dyn Kernel<T>>, KernelError> {
+    let kernel: Box<dyn Any> = Box::new(build_kernel(name, parameters)?);
+    let kernel = match kernel.downcast::<Box<dyn Kernel<T>>>() {
+        Ok(kernel) => return Ok(*kernel),
+        Err(kernel) => kernel.downcast::<Box<dyn Kernel>>(),
+    };
+    match kernel {
+        Ok(kernel) => Ok(Box::new(F64Kernel(*kernel))),
+        Err(_) => Err(KernelError::UnsupportedFloat(name.to_string())),
+    }
+}
+
+thread_local! {
+    /// Samples converted to `f64`, reused across evaluations.
+    static F64_SAMPLES: RefCell<(Vec<f64>, Vec<f64>)> =
+        const { RefCell::new((Vec::new(), Vec::new())) };
+}
+
+/// Evaluates an `f64` kernel on samples of another float type.
+struct F64Kernel(Box<dyn Kernel>);
+
+impl F64Kernel {
+    /// Calls `compute` with `x` and `y` converted to `f64`.
+    fn with_f64<T: Float>(x: &[T], y: &[T], compute: impl FnOnce(&[f64], &[f64]) -> f64) -> T {
+        F64_SAMPLES.with(|samples| {
+            let (x_f64, y_f64) = &mut *samples.borrow_mut();
+            x_f64.clear();
+            x_f64.extend(x.iter().map(|x_i| x_i.as_f64()));
+            y_f64.clear();
+            y_f64.extend(y.iter().map(|y_i| y_i.as_f64()));
+            T::cast(compute(x_f64, y_f64))
+        })
+    }
+}
+
+impl<T: Float> Kernel<T> for F64Kernel {
+    fn compute(&self, x: &[T], y: &[T]) -> T {
+        Self::with_f64(x, y, |x, y| self.0.compute(x, y))
+    }
+
+    fn compute_sparse(&self, x: SparseRow<T>, y: SparseRow<T>) -> T {
+        Self::with_f64(x.values, y.values, |x_values, y_values| {
+            self.0.compute_sparse(
+                SparseRow {
+                    indices: x.indices,
+                    values: x_values,
+                },
+                SparseRow {
+                    indices: y.indices,
+                    values: y_values,
+                },
+            )
+        })
+    }
+
+    fn type_of(&self) -> KernelType {
+        self.0.type_of()
+    }
+}
+
+#[derive(Debug)]
+pub enum KernelError {
+    /// no constructor is registered under this name
+    UnknownKernel(String),
+    /// the parameters can not be serialized or the registered constructor rejected them
+    InvalidParameters {
+        name: String,
+        source: serde_json::Error,
+    },
+    /// the kernel can not be evaluated with the float type of the model
+    UnsupportedFloat(String),
+}
+
+impl fmt::Display for KernelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KernelError::UnknownKernel(name) => write!(
+                f,
+                "unknown kernel `{name}`, register it with `kernel::register_kernel` before loading"
+            ),
+            KernelError::InvalidParameters { name, source } => {
+                write!(f, "invalid parameters for kernel `{name}`: {source}")
+            }
+            KernelError::UnsupportedFloat(name) => {
+                write!(
+                    f,
+                    "kernel `{name}` can not be evaluated with this float type"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for KernelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KernelError::UnknownKernel(_) | KernelError::UnsupportedFloat(_) => None,
+            KernelError::InvalidParameters { source, .. } => Some(source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{kernel::KernelType, sparse::SparseVector, Parameters, SVC};
+
+    #[derive(Serialize, Deserialize)]
+    struct L1Kernel {
+        scale: f64,
+    }
+
+    impl Kernel for L1Kernel {
+        fn compute(&self, x: &[f64], y: &[f64]) -> f64 {
+            let distance: f64 = x.iter().zip(y.iter()).map(|(xi, yi)| (xi - yi).abs()).sum();
+            (-self.scale * distance).exp()
+        }
+
+        fn type_of(&self) -> KernelType {
+            KernelType::custom("l1", self).expect("the scale serializes")
+        }
+    }
+
+    fn l1_kernel(parameters: Value) -> serde_json::Result<Box<dyn Kernel>> {
+        Ok(Box::new(serde_json::from_value::<L1Kernel>(parameters)?))
+    }
+
+    #[test]
+    fn custom_kernel_round_trip() {
+        register_kernel("l1", l1_kernel);
+        assert!(is_registered("l1"));
+
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(Box::new(L1Kernel { scale: 0.5 }));
+        let svc = SVC::new(parameters);
+
+        let json = serde_json::to_string(&svc).unwrap();
+        let svc: SVC = serde_json::from_str(&json).unwrap();
+        let json_again = serde_json::to_string(&svc).unwrap();
+
+        assert_eq!(json, json_again);
+    }
+
+    #[test]
+    fn unknown_kernel() {
+        let kernel_type = KernelType::Custom {
+            name: "not_registered".to_string(),
+            parameters: Value::Null,
+        };
+        let error = kernel_type.new::<f64>().err().unwrap();
+        assert!(matches!(error, KernelError::UnknownKernel(ref name) if name == "not_registered"));
+
+        let json = r#"{"kernel":{"Custom":{"name":"not_registered","parameters":null}},"c":1.0,"tol":0.001,"epochs":2}"#;
+        let error = serde_json::from_str::<Parameters>(json).err().unwrap();
+        assert!(error
+            .to_string()
+            .contains("unknown kernel `not_registered`"));
+    }
+
+    #[test]
+    fn custom_kernel_f32() {
+        register_kernel("l1_f32", l1_kernel);
+        let parameters = serde_json::json!({ "scale": 0.5 });
+        let kernel = build_kernel_as::<f32>("l1_f32", parameters.clone()).unwrap();
+        let expected = L1Kernel { scale: 0.5 }.compute(&[1.0, 0.0, 2.0], &[0.0, 0.0, -1.0]);
+
+        let (x, y) = ([1.0f32, 0.0, 2.0], [0.0f32, 0.0, -1.0]);
+        assert_eq!(kernel.compute(&x, &y), expected as f32);
+        let (x, y) = (SparseVector::from_dense(&x), SparseVector::from_dense(&y));
+        assert_eq!(
+            kernel.compute_sparse(x.as_row(), y.as_row()),
+            expected as f32
+        );
+        assert_eq!(
+            build_kernel_as::<f64>("l1_f32", parameters)
+                .unwrap()
+                .compute(&[1.0], &[0.0]),
+            (-0.5f64).exp()
+        );
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        register_kernel("l1_invalid", l1_kernel);
+        let error = build_kernel("l1_invalid", Value::Null).err().unwrap();
+        assert!(matches!(error, KernelError::InvalidParameters { .. }));
+
+        // JSON objects need string keys
+        let unserializable = HashMap::from([((1, 2), 0.5)]);
+        assert!(matches!(
+            KernelType::custom("pairs", &unserializable),
+            Err(KernelError::InvalidParameters { name, .. }) if name == "pairs"
+        ));
+    }
+}
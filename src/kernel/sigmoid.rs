@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    float::Float,
+    sparse::{self, SparseRow},
+    Kernel,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct SigmoidKernel {
+    gamma: f64,
+    coef0: f64,
+}
+
+impl Default for SigmoidKernel {
+    fn default() -> Self {
+        SigmoidKernel {
+            gamma: 1.0,
+            coef0: 0.0,
+        }
+    }
+}
+
+impl SigmoidKernel {
+    pub fn new(gamma: f64, coef0: f64) -> SigmoidKernel {
+        SigmoidKernel { gamma, coef0 }
+    }
+
+    pub fn with_gamma(mut self, gamma: f64) -> SigmoidKernel {
+        self.gamma = gamma;
+        self
+    }
+
+    pub fn with_coef0(mut self, coef0: f64) -> SigmoidKernel {
+        self.coef0 = coef0;
+        self
+    }
+}
+
+impl<T: Float> Kernel<T> for SigmoidKernel {
+    fn compute(&self, x: &[T], y: &[T]) -> T {
+        let dot: T = x.iter().zip(y.iter()).map(|(&xi, &yi)| xi * yi).sum();
+        (T::cast(self.gamma) * dot + T::cast(self.coef0)).tanh()
+    }
+
+    fn compute_sparse(&self, x: SparseRow<T>, y: SparseRow<T>) -> T {
+        (T::cast(self.gamma) * sparse::dot(x, y) + T::cast(self.coef0)).tanh()
+    }
+
+    fn type_of(&self) -> super::KernelType {
+        super::KernelType::Sigmoid {
+            gamma: self.gamma,
+            coef0: self.coef0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sigmoid_kernel() {
+        let v1 = vec![1., 2., 3.];
+        let v2 = vec![4., 5., 6.];
+
+        let result = SigmoidKernel::default()
+            .with_gamma(0.01)
+            .with_coef0(0.1)
+            .compute(&v1, &v2);
+
+        assert!((0.3969f64 - result).abs() < 1e-4);
+    }
+}
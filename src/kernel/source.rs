@@ -0,0 +1,103 @@
+use super::Kernel;
+use crate::{float::Float, matrix::Matrix, sparse::CsrMatrix};
+
+/// Kernel values between training samples, addressed by sample index.
+///
+/// Optimizers only need `K(x_i, x_j)`, so they can run against feature vectors and a
+/// kernel function as well as against a precomputed Gram matrix.
+pub trait KernelSource<T: Float = f64>: Sync + Send {
+    /// number of training samples
+    fn n_samples(&self) -> usize;
+    /// kernel value between the samples `i` and `j`
+    fn compute(&self, i: usize, j: usize) -> T;
+}
+
+/// Evaluates a kernel function on feature vectors.
+pub struct FeatureKernelSource<'a, T: Float = f64> {
+    x: &'a dyn Matrix<T>,
+    kernel: &'a dyn Kernel<T>,
+}
+
+impl<'a, T: Float> FeatureKernelSource<'a, T> {
+    pub fn new(x: &'a dyn Matrix<T>, kernel: &'a dyn Kernel<T>) -> Self {
+        FeatureKernelSource { x, kernel }
+    }
+}
+
+impl<T: Float> KernelSource<T> for FeatureKernelSource<'_, T> {
+    fn n_samples(&self) -> usize {
+        self.x.n_rows()
+    }
+
+    fn compute(&self, i: usize, j: usize) -> T {
+        self.kernel.compute(self.x.row(i), self.x.row(j))
+    }
+}
+
+/// Evaluates a kernel function on the rows of a sparse matrix.
+pub struct SparseKernelSource<'a, T: Float = f64> {
+    x: &'a CsrMatrix<T>,
+    kernel: &'a dyn Kernel<T>,
+}
+
+impl<'a, T: Float> SparseKernelSource<'a, T> {
+    pub fn new(x: &'a CsrMatrix<T>, kernel: &'a dyn Kernel<T>) -> Self {
+        SparseKernelSource { x, kernel }
+    }
+}
+
+impl<T: Float> KernelSource<T> for SparseKernelSource<'_, T> {
+    fn n_samples(&self) -> usize {
+        self.x.n_rows()
+    }
+
+    fn compute(&self, i: usize, j: usize) -> T {
+        self.kernel.compute_sparse(self.x.row(i), self.x.row(j))
+    }
+}
+
+/// Reads kernel values from a precomputed n×n Gram matrix.
+pub struct PrecomputedKernelSource<'a, T: Float = f64> {
+    gram: &'a dyn Matrix<T>,
+}
+
+impl<'a, T: Float> PrecomputedKernelSource<'a, T> {
+    pub fn new(gram: &'a dyn Matrix<T>) -> Self {
+        PrecomputedKernelSource { gram }
+    }
+}
+
+impl<T: Float> KernelSource<T> for PrecomputedKernelSource<'_, T> {
+    fn n_samples(&self) -> usize {
+        self.gram.n_rows()
+    }
+
+    fn compute(&self, i: usize, j: usize) -> T {
+        self.gram.row(i)[j]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::LinearKernel;
+
+    #[test]
+    fn feature_and_precomputed_sources_agree() {
+        let x = vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]];
+        let kernel = LinearKernel::default();
+        let features = FeatureKernelSource::new(&x, &kernel);
+
+        let gram: Vec<Vec<f64>> = (0..x.len())
+            .map(|i| (0..x.len()).map(|j| features.compute(i, j)).collect())
+            .collect();
+        let precomputed = PrecomputedKernelSource::new(&gram);
+
+        assert_eq!(features.n_samples(), precomputed.n_samples());
+        for i in 0..x.len() {
+            for j in 0..x.len() {
+                assert_eq!(features.compute(i, j), precomputed.compute(i, j));
+            }
+        }
+    }
+}
@@ -6,21 +6,46 @@
 /// * [Smartcore](https://github.com/smartcorelib/smartcore/blob/development/src/svm/svc.rs)
 ///
 pub mod cache;
+pub mod dataset;
+pub mod error;
+pub mod float;
 pub mod kernel;
+pub mod libsvm;
+pub mod linear_svc;
+pub mod matrix;
+pub mod multiclass;
+mod nu_optimizer;
+pub mod one_class;
 pub mod optimizer;
 pub mod parameters;
+pub mod persistence;
+pub mod platt;
 pub mod smartcore_optimizer;
+pub mod sparse;
 pub mod support_vector;
 pub mod svc;
 pub mod svm;
+pub mod svr;
+#[cfg(test)]
+mod test_utils;
+mod validation;
 
+pub use dataset::Dataset;
+pub use error::{Error, Result};
+pub use float::Float;
 pub use kernel::Kernel;
 pub use kernel::RBFKernel;
+pub use linear_svc::LinearSVC;
+pub use matrix::{DenseMatrix, Matrix};
+pub use multiclass::{MulticlassSVC, MulticlassStrategy};
+pub use one_class::OneClassSVM;
 pub use parameters::Parameters;
+pub use sparse::{CsrMatrix, SparseVector};
 pub use svc::SVC;
+pub use svr::SVR;
 
 /// Used types
-pub type X = Vec<Vec<f64>>;
+pub type X<T = f64> = Vec<Vec<T>>;
 pub type Y = Vec<i32>;
-pub type W = Vec<f64>;
-pub type B = f64;
+pub type W<T = f64> = Vec<T>;
+pub type B<T = f64> = T;
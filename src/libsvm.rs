@@ -0,0 +1,295 @@
+//! Text model files of [libsvm](https://www.csie.ntu.edu.tw/~cjlin/libsvm/).
+//!
+//! A model file has a header of `key value` lines followed by the support vectors after
+//! the `SV` line, one per line as the coefficient and the sparse features with 1-based
+//! indices:
+//!
+//! ```text
+//! svm_type c_svc
+//! kernel_type rbf
+//! gamma 0.5
+//! nr_class 2
+//! total_sv 3
+//! rho 0.25
+//! label 1 -1
+//! nr_sv 2 1
+//! SV
+//! 1 1:0.5 3:1
+//! 0.5 2:1
+//! -1.5 1:-1 2:-0.5
+//! ```
+//!
+//! Binary C-SVC and nu-SVC models with a linear or RBF kernel are supported.
+
+use std::{
+    fmt::Display,
+    io::{BufRead, Write},
+    str::FromStr,
+};
+
+use crate::{
+    error::{Error, Result},
+    float::Float,
+    kernel::KernelType,
+    parameters::Formulation,
+    sparse::SparseVector,
+};
+
+/// Binary classifier in the form of libsvm, the decision function
+/// `sum_i coef_i K(sv_i, x) - rho` is positive for the first class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibsvmModel<T: Float = f64> {
+    pub formulation: Formulation,
+    /// [`KernelType::Linear`] or [`KernelType::RBF`]
+    pub kernel: KernelType,
+    /// labels of the first and the second class
+    pub labels: [i32; 2],
+    pub rho: T,
+    /// Platt scaling parameters of the probability of the first class
+    pub prob_a: Option<f64>,
+    pub prob_b: Option<f64>,
+    /// number of support vectors of each class, those of the first class come first
+    pub n_sv: [usize; 2],
+    /// coefficients `y_i alpha_i` of the support vectors, `y_i = 1` for the first class
+    pub coef: Vec<T>,
+    /// support vectors with 0-based feature indices
+    pub support_vectors: Vec<SparseVector<T>>,
+}
+
+impl<T: Float> LibsvmModel<T> {
+    /// Reads a model file, errors name the offending line.
+    pub fn read(reader: impl BufRead) -> Result<Self> {
+        let mut formulation = None;
+        let mut kernel_type = None;
+        let mut gamma = None;
+        let mut rho = None;
+        let mut labels = None;
+        let mut n_sv = None;
+        let mut total_sv = None;
+        let (mut prob_a, mut prob_b) = (None, None);
+        let mut coef = Vec::new();
+        let mut support_vectors = Vec::new();
+        let mut in_header = true;
+
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let error = |message: String| Error::Format(format!("line {}: {message}", number + 1));
+            let mut tokens = line.split_whitespace();
+            let Some(first) = tokens.next() else {
+                continue;
+            };
+            if !in_header {
+                coef.push(T::cast(parse(first).map_err(error)?));
+                support_vectors.push(parse_features(tokens).map_err(error)?);
+                continue;
+            }
+            let values: Vec<&str> = tokens.collect();
+            match first {
+                "svm_type" => {
+                    formulation = Some(match single(&values).map_err(error)? {
+                        "c_svc" => Formulation::C,
+                        "nu_svc" => Formulation::Nu,
+                        other => {
+                            return Err(error(format!(
+                                "svm_type {other} is not supported, expected c_svc or nu_svc"
+                            )))
+                        }
+                    })
+                }
+                "kernel_type" => kernel_type = Some(single(&values).map_err(error)?.to_string()),
+                "gamma" => gamma = Some(parse(single(&values).map_err(error)?).map_err(error)?),
+                "nr_class" => {
+                    let n_class: usize = parse(single(&values).map_err(error)?).map_err(error)?;
+                    if n_class != 2 {
+                        return Err(error(format!(
+                            "{n_class} classes are not supported, expected a binary model"
+                        )));
+                    }
+                }
+                "total_sv" => {
+                    total_sv = Some(parse::<usize>(single(&values).map_err(error)?).map_err(error)?)
+                }
+                "rho" => rho = Some(parse::<f64>(single(&values).map_err(error)?).map_err(error)?),
+                "label" => labels = Some(pair(&values).map_err(error)?),
+                "nr_sv" => n_sv = Some(pair(&values).map_err(error)?),
+                "probA" => prob_a = Some(parse(single(&values).map_err(error)?).map_err(error)?),
+                "probB" => prob_b = Some(parse(single(&values).map_err(error)?).map_err(error)?),
+                "SV" => in_header = false,
+                // e.g. degree and coef0 of other kernels
+                _ => {}
+            }
+        }
+
+        let missing = |key: &str| Error::Format(format!("missing {key}"));
+        let kernel = match kernel_type.ok_or_else(|| missing("kernel_type"))?.as_str() {
+            "linear" => KernelType::Linear,
+            "rbf" => KernelType::RBF(gamma.ok_or_else(|| missing("gamma"))?),
+            other => {
+                return Err(Error::Format(format!(
+                    "kernel_type {other} is not supported, expected linear or rbf"
+                )))
+            }
+        };
+        if in_header {
+            return Err(missing("SV section"));
+        }
+        let n_sv: [usize; 2] = n_sv.ok_or_else(|| missing("nr_sv"))?;
+        let total_sv = total_sv.unwrap_or(coef.len());
+        if n_sv[0] + n_sv[1] != total_sv || coef.len() != total_sv {
+            return Err(Error::Format(format!(
+                "nr_sv {} {} and total_sv {total_sv} do not match {} support vectors",
+                n_sv[0],
+                n_sv[1],
+                coef.len()
+            )));
+        }
+        Ok(LibsvmModel {
+            formulation: formulation.ok_or_else(|| missing("svm_type"))?,
+            kernel,
+            labels: labels.ok_or_else(|| missing("label"))?,
+            rho: T::cast(rho.ok_or_else(|| missing("rho"))?),
+            prob_a,
+            prob_b,
+            n_sv,
+            coef,
+            support_vectors,
+        })
+    }
+
+    /// Writes the model in the format read by libsvm.
+    pub fn write(&self, mut writer: impl Write) -> Result<()> {
+        let svm_type = match self.formulation {
+            Formulation::C => "c_svc",
+            Formulation::Nu => "nu_svc",
+        };
+        writeln!(writer, "svm_type {svm_type}")?;
+        match &self.kernel {
+            KernelType::Linear => writeln!(writer, "kernel_type linear")?,
+            KernelType::RBF(gamma) => writeln!(writer, "kernel_type rbf\ngamma {gamma}")?,
+            other => {
+                return Err(Error::Format(format!(
+                    "kernel {other:?} can not be written, expected linear or rbf"
+                )))
+            }
+        }
+        writeln!(writer, "nr_class 2")?;
+        writeln!(writer, "total_sv {}", self.coef.len())?;
+        writeln!(writer, "rho {}", self.rho)?;
+        writeln!(writer, "label {} {}", self.labels[0], self.labels[1])?;
+        if let (Some(prob_a), Some(prob_b)) = (self.prob_a, self.prob_b) {
+            writeln!(writer, "probA {prob_a}\nprobB {prob_b}")?;
+        }
+        writeln!(writer, "nr_sv {} {}", self.n_sv[0], self.n_sv[1])?;
+        writeln!(writer, "SV")?;
+        for (coef, support_vector) in self.coef.iter().zip(&self.support_vectors) {
+            write!(writer, "{coef}")?;
+            let row = support_vector.as_row();
+            for (index, value) in row.indices.iter().zip(row.values) {
+                write!(writer, " {}:{value}", index + 1)?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse<V: FromStr>(token: &str) -> std::result::Result<V, String>
+where
+    V::Err: Display,
+{
+    token
+        .parse()
+        .map_err(|err| format!("invalid value \"{token}\": {err}"))
+}
+
+fn single<'a>(values: &[&'a str]) -> std::result::Result<&'a str, String> {
+    match values {
+        &[value] => Ok(value),
+        _ => Err(format!("expected one value, got {}", values.len())),
+    }
+}
+
+fn pair<V: FromStr>(values: &[&str]) -> std::result::Result<[V; 2], String>
+where
+    V::Err: Display,
+{
+    match values {
+        &[first, second] => Ok([parse(first)?, parse(second)?]),
+        _ => Err(format!("expected two values, got {}", values.len())),
+    }
+}
+
+/// Parses `index:value` tokens with increasing 1-based indices.
+pub(crate) fn parse_features<'a, T: Float>(
+    tokens: impl Iterator<Item = &'a str>,
+) -> std::result::Result<SparseVector<T>, String> {
+    let mut indices = Vec::new();
+    let mut values = Vec::new();
+    for token in tokens {
+        let (index, value) = token
+            .split_once(':')
+            .ok_or_else(|| format!("expected index:value, got \"{token}\""))?;
+        let index: usize = parse(index)?;
+        if index == 0 {
+            return Err("feature indices start at 1".to_string());
+        }
+        if indices.last().is_some_and(|&last| index - 1 <= last) {
+            return Err(format!("feature index {index} is not increasing"));
+        }
+        indices.push(index - 1);
+        values.push(T::cast(parse(value)?));
+    }
+    SparseVector::new(indices, values).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = "svm_type c_svc
+kernel_type rbf
+gamma 0.5
+nr_class 2
+total_sv 3
+rho 0.25
+label 1 -1
+probA -2.5
+probB 0.125
+nr_sv 2 1
+SV
+1 1:0.5 3:1
+0.5 2:1
+-1.5 1:-1 2:-0.5
+";
+
+    #[test]
+    fn read_write_model() {
+        let model = LibsvmModel::<f64>::read(MODEL.as_bytes()).unwrap();
+        assert_eq!(model.kernel, KernelType::RBF(0.5));
+        assert_eq!(model.labels, [1, -1]);
+        assert_eq!(model.n_sv, [2, 1]);
+        assert_eq!(model.coef, vec![1.0, 0.5, -1.5]);
+        assert_eq!(model.support_vectors[0].as_row().indices, &[0, 2]);
+        assert_eq!(model.prob_b, Some(0.125));
+
+        let mut text = Vec::new();
+        model.write(&mut text).unwrap();
+        assert_eq!(String::from_utf8(text).unwrap(), MODEL);
+    }
+
+    #[test]
+    fn read_invalid_model() {
+        let error = |text: &str| match LibsvmModel::<f64>::read(text.as_bytes()) {
+            Err(Error::Format(message)) => message,
+            other => panic!("expected a format error, got {other:?}"),
+        };
+        assert_eq!(
+            error(&MODEL.replace("1:-1 2:-0.5", "2:-1 1:-0.5")),
+            "line 14: feature index 1 is not increasing"
+        );
+        assert!(error(&MODEL.replace("nr_class 2", "nr_class 3")).starts_with("line 4:"));
+        assert!(error(&MODEL.replace("rbf", "polynomial")).contains("not supported"));
+        assert!(error(&MODEL.replace("nr_sv 2 1", "nr_sv 2 2")).contains("nr_sv"));
+        assert_eq!(error(&MODEL.replace("rho 0.25\n", "")), "missing rho");
+    }
+}
@@ -0,0 +1,661 @@
+// source: https://www.csie.ntu.edu.tw/~cjlin/papers/cddual.pdf
+// source: https://www.csie.ntu.edu.tw/~cjlin/papers/l1.pdf
+// source: https://github.com/cjlin1/liblinear/blob/master/linear.cpp
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    float::Float,
+    kernel::KernelType,
+    matrix::Matrix,
+    optimizer::{Convergence, ConvergenceStatus},
+    parameters::{Loss, Parameters, Penalty},
+    smartcore_optimizer::permutate,
+    sparse::CsrMatrix,
+    svm::SVM,
+    validation,
+};
+
+/// Linear support vector classifier trained in the primal space.
+///
+/// Instead of the kernel SMO it uses the coordinate descent solvers of liblinear, the
+/// cost of a pass over the data is linear in the number of non-zero features:
+///
+/// * `Penalty::L2` solves the dual problem for `Loss::Hinge` and `Loss::SquaredHinge`
+/// * `Penalty::L1` solves the primal problem for `Loss::SquaredHinge`
+///
+/// The bias is learned as the weight of an additional constant feature and is therefore
+/// regularized as well. The kernel of the parameters has to be linear.
+///
+/// `Parameters::max_iter` limits the number of passes over the data, 1000 by default.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "T: Float")]
+pub struct LinearSVC<T: Float = f64> {
+    parameters: Parameters<T>,
+    coef: Option<Vec<T>>,
+    intercept: Option<T>,
+    n_features: Option<usize>,
+    /// sorted labels of the negative and the positive class
+    classes: Option<Vec<i32>>,
+    /// how the solver stopped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    convergence: Option<Convergence>,
+}
+
+/// Default number of passes over the data.
+const DEFAULT_MAX_ITER: usize = 1000;
+
+/// Training or test samples of a [`LinearSVC`].
+#[derive(Clone, Copy)]
+enum Samples<'a, T: Float> {
+    Dense(&'a dyn Matrix<T>),
+    Sparse(&'a CsrMatrix<T>),
+}
+
+impl<T: Float> Samples<'_, T> {
+    fn n_rows(&self) -> usize {
+        match self {
+            Samples::Dense(x) => x.n_rows(),
+            Samples::Sparse(x) => x.n_rows(),
+        }
+    }
+
+    /// `w · x_i`
+    fn dot(&self, i: usize, w: &[T]) -> T {
+        match self {
+            Samples::Dense(x) => x.row(i).iter().zip(w.iter()).map(|(&x, &w)| x * w).sum(),
+            Samples::Sparse(x) => {
+                let x_i = x.row(i);
+                x_i.indices
+                    .iter()
+                    .zip(x_i.values.iter())
+                    .map(|(&index, &value)| value * w[index])
+                    .sum()
+            }
+        }
+    }
+
+    /// `x_i · x_i`
+    fn squared_norm(&self, i: usize) -> T {
+        let values = match self {
+            Samples::Dense(x) => x.row(i),
+            Samples::Sparse(x) => x.row(i).values,
+        };
+        values.iter().map(|&v| v * v).sum()
+    }
+
+    /// `w += a * x_i`
+    fn axpy(&self, i: usize, a: T, w: &mut [T]) {
+        match self {
+            Samples::Dense(x) => {
+                for (w, &x) in w.iter_mut().zip(x.row(i).iter()) {
+                    *w += a * x;
+                }
+            }
+            Samples::Sparse(x) => {
+                let x_i = x.row(i);
+                for (&index, &value) in x_i.indices.iter().zip(x_i.values.iter()) {
+                    w[index] += a * value;
+                }
+            }
+        }
+    }
+
+    /// Non-zero entries of every feature as pairs of sample and value.
+    fn columns(&self, n_features: usize) -> Vec<Vec<(usize, T)>> {
+        let mut columns = vec![Vec::new(); n_features];
+        for i in 0..self.n_rows() {
+            match self {
+                Samples::Dense(x) => {
+                    for (j, &value) in x.row(i).iter().enumerate() {
+                        if value != T::zero() {
+                            columns[j].push((i, value));
+                        }
+                    }
+                }
+                Samples::Sparse(x) => {
+                    let x_i = x.row(i);
+                    for (&j, &value) in x_i.indices.iter().zip(x_i.values.iter()) {
+                        columns[j].push((i, value));
+                    }
+                }
+            }
+        }
+        columns
+    }
+}
+
+unsafe impl<T: Float> Sync for LinearSVC<T> {}
+unsafe impl<T: Float> Send for LinearSVC<T> {}
+
+impl<T: Float> LinearSVC<T> {
+    pub fn new(parameters: Parameters<T>) -> LinearSVC<T> {
+        LinearSVC {
+            parameters,
+            coef: None,
+            intercept: None,
+            n_features: None,
+            classes: None,
+            convergence: None,
+        }
+    }
+
+    /// Weights of the features.
+    pub fn coef_(&self) -> Option<&[T]> {
+        self.coef.as_deref()
+    }
+
+    /// Bias of the decision function.
+    pub fn intercept_(&self) -> Option<T> {
+        self.intercept
+    }
+
+    /// Sorted class labels seen during training, the second one is the positive class.
+    pub fn classes(&self) -> Option<&[i32]> {
+        self.classes.as_deref()
+    }
+
+    /// How the solver stopped during training, `iterations` counts the passes over the
+    /// data.
+    pub fn convergence(&self) -> Option<Convergence> {
+        self.convergence
+    }
+
+    /// Computes the decision values of the samples in `x`.
+    pub fn decision_function(&self, x: &(impl Matrix<T> + ?Sized)) -> Result<Vec<T>> {
+        validation::check_predict_input(&x, self.n_features)?;
+        self.decision_values(Samples::Dense(&x))
+    }
+
+    /// Computes the decision values of the sparse samples in `x`.
+    pub fn decision_function_sparse(&self, x: &CsrMatrix<T>) -> Result<Vec<T>> {
+        if let Some(n_features) = self.n_features {
+            if x.n_cols() != n_features {
+                return Err(Error::DimensionMismatch(format!(
+                    "x has {} features but the model was trained with {n_features}",
+                    x.n_cols()
+                )));
+            }
+        }
+        self.decision_values(Samples::Sparse(x))
+    }
+
+    /// Predicts the labels of the sparse samples in `x`.
+    pub fn predict_sparse(&self, x: &CsrMatrix<T>) -> Result<Vec<i32>> {
+        Ok(self.labels(&self.decision_function_sparse(x)?))
+    }
+
+    /// Trains the model on sparse samples.
+    pub fn fit_sparse(&mut self, x: &CsrMatrix<T>, y: &[i32]) -> Result<()> {
+        validation::check_n_samples(x.n_rows(), Some(y.len()))?;
+        self.fit_samples(Samples::Sparse(x), x.n_cols(), y)
+    }
+
+    fn decision_values(&self, x: Samples<T>) -> Result<Vec<T>> {
+        let coef = self.coef.as_ref().ok_or(Error::NotFitted)?;
+        let intercept = self.intercept.ok_or(Error::NotFitted)?;
+        Ok((0..x.n_rows())
+            .map(|i| x.dot(i, coef) + intercept)
+            .collect())
+    }
+
+    /// Maps decision values to the class labels.
+    fn labels(&self, decision_values: &[T]) -> Vec<i32> {
+        let (negative, positive) = match self.classes.as_deref() {
+            Some(&[negative, positive]) => (negative, positive),
+            _ => (-1, 1),
+        };
+        decision_values
+            .iter()
+            .map(|&y| if y > T::zero() { positive } else { negative })
+            .collect()
+    }
+
+    fn fit_samples(&mut self, x: Samples<T>, n_features: usize, y: &[i32]) -> Result<()> {
+        if self.parameters.kernel.type_of() != KernelType::Linear {
+            return Err(Error::InvalidParameter(
+                "LinearSVC requires a linear kernel".to_string(),
+            ));
+        }
+        validation::check_c(self.parameters.c)?;
+        let weights = self.parameters.class_weight.sample_weights(y);
+        validation::check_sample_weight(&weights, y.len())?;
+
+        let mut classes = y.to_vec();
+        classes.sort_unstable();
+        classes.dedup();
+        if classes.len() != 2 {
+            return Err(Error::InvalidLabel(format!(
+                "LinearSVC requires exactly two classes, got {} (use MulticlassSVC for more)",
+                classes.len()
+            )));
+        }
+        let y: Vec<T> = y
+            .iter()
+            .map(|&label| {
+                if label == classes[1] {
+                    T::one()
+                } else {
+                    -T::one()
+                }
+            })
+            .collect();
+        let c: Vec<T> = weights
+            .iter()
+            .map(|&weight| T::cast(self.parameters.c * weight))
+            .collect();
+
+        let (coef, intercept, convergence) = match (self.parameters.penalty, self.parameters.loss) {
+            (Penalty::L2, loss) => self.dual_coordinate_descent(x, n_features, &y, &c, loss),
+            (Penalty::L1, Loss::SquaredHinge) => {
+                self.primal_coordinate_descent(x, n_features, &y, &c)
+            }
+            (Penalty::L1, Loss::Hinge) => {
+                return Err(Error::InvalidParameter(
+                    "the L1 penalty is only supported with the squared hinge loss".to_string(),
+                ))
+            }
+        };
+
+        self.coef = Some(coef);
+        self.intercept = Some(intercept);
+        self.n_features = Some(n_features);
+        self.classes = Some(classes);
+        self.convergence = Some(convergence);
+        Ok(())
+    }
+
+    /// Dual coordinate descent with shrinking for the L2 penalty.
+    ///
+    /// Solves `min 1/2 a^T (Q + D) a - sum_i a_i` subject to `0 <= a_i <= u_i` with
+    /// `Q_ij = y_i y_j (x_i · x_j + 1)`. For the hinge loss `D = 0` and `u_i = c_i`, for
+    /// the squared hinge loss `D_ii = 1 / (2 c_i)` and `u_i = inf`.
+    fn dual_coordinate_descent(
+        &self,
+        x: Samples<T>,
+        n_features: usize,
+        y: &[T],
+        c: &[T],
+        loss: Loss,
+    ) -> (Vec<T>, T, Convergence) {
+        let n = y.len();
+        let (zero, one) = (T::zero(), T::one());
+        let (diag, upper): (Vec<T>, Vec<T>) = c
+            .iter()
+            .map(|&c_i| match loss {
+                Loss::Hinge => (zero, c_i),
+                Loss::SquaredHinge => (one / (T::cast(2.0) * c_i), T::infinity()),
+            })
+            .unzip();
+        let qd: Vec<T> = (0..n).map(|i| diag[i] + x.squared_norm(i) + one).collect();
+
+        let mut alpha = vec![zero; n];
+        let mut w = vec![zero; n_features];
+        let mut b = zero;
+        let mut index: Vec<usize> = (0..n).collect();
+        let mut active_size = n;
+        let mut pg_max_old = T::infinity();
+        let mut pg_min_old = T::neg_infinity();
+        let tol = T::cast(self.parameters.tol);
+        let deadline = self.deadline();
+        let mut iterations = 0;
+
+        let (status, kkt_violation) = loop {
+            let mut pg_max_new = T::neg_infinity();
+            let mut pg_min_new = T::infinity();
+            let permutation = permutate(active_size, Some(100 + iterations));
+            let active: Vec<usize> = permutation.iter().map(|&s| index[s]).collect();
+            index[..active_size].copy_from_slice(&active);
+
+            let mut s = 0;
+            while s < active_size {
+                let i = index[s];
+                let g = y[i] * (x.dot(i, &w) + b) - one + diag[i] * alpha[i];
+
+                // projected gradient, variables stuck at a bound are shrunken
+                let pg = if alpha[i] == zero {
+                    if g > pg_max_old {
+                        active_size -= 1;
+                        index.swap(s, active_size);
+                        continue;
+                    }
+                    g.min(zero)
+                } else if alpha[i] == upper[i] {
+                    if g < pg_min_old {
+                        active_size -= 1;
+                        index.swap(s, active_size);
+                        continue;
+                    }
+                    g.max(zero)
+                } else {
+                    g
+                };
+                pg_max_new = pg_max_new.max(pg);
+                pg_min_new = pg_min_new.min(pg);
+
+                if pg.abs() > T::cast(1e-12) {
+                    let old = alpha[i];
+                    alpha[i] = (alpha[i] - g / qd[i]).max(zero).min(upper[i]);
+                    let d = (alpha[i] - old) * y[i];
+                    x.axpy(i, d, &mut w);
+                    b += d;
+                }
+                s += 1;
+            }
+            iterations += 1;
+
+            let kkt_violation = pg_max_new - pg_min_new;
+            if kkt_violation <= tol {
+                if active_size == n {
+                    break (ConvergenceStatus::Converged, kkt_violation);
+                }
+                // check the shrunken variables once more
+                active_size = n;
+                pg_max_old = T::infinity();
+                pg_min_old = T::neg_infinity();
+            } else {
+                pg_max_old = if pg_max_new <= zero {
+                    T::infinity()
+                } else {
+                    pg_max_new
+                };
+                pg_min_old = if pg_min_new >= zero {
+                    T::neg_infinity()
+                } else {
+                    pg_min_new
+                };
+            }
+            if let Some(status) = self.budget_status(iterations, deadline) {
+                break (status, kkt_violation);
+            }
+        };
+
+        (w, b, convergence(status, iterations, kkt_violation))
+    }
+
+    /// Primal coordinate descent with Newton steps and line search for the L1 penalty
+    /// and the squared hinge loss.
+    ///
+    /// Minimizes `||w||_1 + sum_i c_i max(0, 1 - y_i (w · x_i + b))^2`.
+    fn primal_coordinate_descent(
+        &self,
+        x: Samples<T>,
+        n_features: usize,
+        y: &[T],
+        c: &[T],
+    ) -> (Vec<T>, T, Convergence) {
+        let n = y.len();
+        let (zero, one, two) = (T::zero(), T::one(), T::cast(2.0));
+        let sigma = T::cast(0.01);
+        let max_line_search = 20;
+
+        // the last column is the constant feature of the bias
+        let mut columns = x.columns(n_features);
+        columns.push((0..n).map(|i| (i, one)).collect());
+        let mut w = vec![zero; n_features + 1];
+        // slack `1 - y_i (w · x_i + b)` of every sample
+        let mut slack = vec![one; n];
+        let mut violation_init = None;
+        let deadline = self.deadline();
+        let mut iterations = 0;
+
+        let (status, kkt_violation) = loop {
+            let mut violation = zero;
+            for j in permutate(n_features + 1, Some(100 + iterations)) {
+                let column = &columns[j];
+                let (mut g, mut h) = (zero, zero);
+                for &(i, value) in column {
+                    if slack[i] > zero {
+                        g -= two * c[i] * y[i] * value * slack[i];
+                        h += two * c[i] * value * value;
+                    }
+                }
+                let h = h.max(T::cast(1e-12));
+                let (g_plus, g_minus) = (g + one, g - one);
+
+                violation += if w[j] > zero {
+                    g_plus.abs()
+                } else if w[j] < zero {
+                    g_minus.abs()
+                } else if g_plus < zero {
+                    -g_plus
+                } else if g_minus > zero {
+                    g_minus
+                } else {
+                    zero
+                };
+
+                let mut d = if g_plus < h * w[j] {
+                    -g_plus / h
+                } else if g_minus > h * w[j] {
+                    -g_minus / h
+                } else {
+                    -w[j]
+                };
+                if d.abs() < T::cast(1e-12) {
+                    continue;
+                }
+
+                let mut delta = g * d + (w[j] + d).abs() - w[j].abs();
+                for _ in 0..max_line_search {
+                    let loss_change: T = column
+                        .iter()
+                        .map(|&(i, value)| {
+                            let new = (slack[i] - d * y[i] * value).max(zero);
+                            let old = slack[i].max(zero);
+                            c[i] * (new * new - old * old)
+                        })
+                        .sum();
+                    if (w[j] + d).abs() - w[j].abs() + loss_change <= sigma * delta {
+                        break;
+                    }
+                    d /= two;
+                    delta /= two;
+                }
+
+                w[j] += d;
+                for &(i, value) in column {
+                    slack[i] -= d * y[i] * value;
+                }
+            }
+
+            iterations += 1;
+
+            let violation_init = *violation_init.get_or_insert(violation);
+            if violation <= T::cast(self.parameters.tol) * violation_init {
+                break (ConvergenceStatus::Converged, violation);
+            }
+            if let Some(status) = self.budget_status(iterations, deadline) {
+                break (status, violation);
+            }
+        };
+
+        let b = w.pop().unwrap_or(zero);
+        (w, b, convergence(status, iterations, kkt_violation))
+    }
+
+    /// End of the time budget, the clock is only read if `max_time` is set.
+    fn deadline(&self) -> Option<Instant> {
+        self.parameters
+            .max_time
+            .map(|max_time| Instant::now() + max_time)
+    }
+
+    /// Returns why a solver has to stop after `iterations` passes, `None` if it may
+    /// continue. At least one pass is always made.
+    fn budget_status(
+        &self,
+        iterations: usize,
+        deadline: Option<Instant>,
+    ) -> Option<ConvergenceStatus> {
+        if iterations >= self.parameters.max_iter.unwrap_or(DEFAULT_MAX_ITER) {
+            Some(ConvergenceStatus::IterationLimit)
+        } else if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            Some(ConvergenceStatus::TimeLimit)
+        } else {
+            None
+        }
+    }
+}
+
+fn convergence<T: Float>(
+    status: ConvergenceStatus,
+    iterations: usize,
+    kkt_violation: T,
+) -> Convergence {
+    Convergence {
+        status,
+        iterations,
+        kkt_violation: kkt_violation.max(T::zero()).as_f64(),
+    }
+}
+
+impl<T: Float> SVM<T> for LinearSVC<T> {
+    fn fit(&mut self, x: &(impl Matrix<T> + ?Sized), y: &[i32]) -> Result<()> {
+        let n_features = validation::check_fit_input(&x, Some(y.len()), false)?;
+        self.fit_samples(Samples::Dense(&x), n_features, y)
+    }
+
+    fn predict(&self, x: &(impl Matrix<T> + ?Sized)) -> Result<Vec<i32>> {
+        Ok(self.labels(&self.decision_function(x)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kernel::{LinearKernel, RBFKernel},
+        test_utils::iris_binary,
+    };
+
+    fn parameters(loss: Loss, penalty: Penalty) -> Parameters {
+        let mut parameters = Parameters::default();
+        parameters
+            .with_kernel(Box::new(LinearKernel::new()))
+            .with_c(10.0)
+            .with_loss(loss)
+            .with_penalty(penalty);
+        parameters
+    }
+
+    #[test]
+    fn linear_svc_fit_predict() {
+        let (x, y) = iris_binary();
+        for (loss, penalty) in [
+            (Loss::Hinge, Penalty::L2),
+            (Loss::SquaredHinge, Penalty::L2),
+            (Loss::SquaredHinge, Penalty::L1),
+        ] {
+            let mut svc = LinearSVC::new(parameters(loss, penalty));
+            svc.fit(&x, &y).unwrap();
+
+            assert_eq!(svc.predict(&x).unwrap(), y, "{loss:?} {penalty:?}");
+            assert_eq!(svc.coef_().unwrap().len(), 4);
+            assert!(svc.intercept_().is_some());
+        }
+    }
+
+    #[test]
+    fn linear_svc_convergence() {
+        let (x, y) = iris_binary();
+        for (loss, penalty) in [
+            (Loss::Hinge, Penalty::L2),
+            (Loss::SquaredHinge, Penalty::L1),
+        ] {
+            let mut svc = LinearSVC::new(parameters(loss, penalty));
+            svc.fit(&x, &y).unwrap();
+            let convergence = svc.convergence().unwrap();
+            assert_eq!(convergence.status, ConvergenceStatus::Converged);
+            assert!(convergence.iterations > 1);
+
+            let mut limited = parameters(loss, penalty);
+            limited.with_max_iter(1);
+            let mut svc = LinearSVC::new(limited);
+            svc.fit(&x, &y).unwrap();
+            let convergence = svc.convergence().unwrap();
+            assert_eq!(convergence.status, ConvergenceStatus::IterationLimit);
+            assert_eq!(convergence.iterations, 1);
+
+            let mut timed = parameters(loss, penalty);
+            timed.with_max_time(std::time::Duration::ZERO);
+            let mut svc = LinearSVC::new(timed);
+            svc.fit(&x, &y).unwrap();
+            assert_eq!(
+                svc.convergence().unwrap().status,
+                ConvergenceStatus::TimeLimit
+            );
+
+            let json = serde_json::to_string(&svc).unwrap();
+            let loaded: LinearSVC = serde_json::from_str(&json).unwrap();
+            assert_eq!(loaded.convergence(), svc.convergence());
+        }
+    }
+
+    #[test]
+    fn linear_svc_l1_sparsity() {
+        // only the first feature separates the classes
+        let x: Vec<Vec<f64>> = (0..20)
+            .map(|i| {
+                let label = if i < 10 { -1.0 } else { 1.0 };
+                vec![
+                    label * (1.0 + (i % 3) as f64),
+                    (i % 4) as f64,
+                    (i % 5) as f64,
+                ]
+            })
+            .collect();
+        let y: Vec<i32> = (0..20).map(|i| if i < 10 { -1 } else { 1 }).collect();
+        let mut svc = LinearSVC::new(parameters(Loss::SquaredHinge, Penalty::L1));
+        svc.fit(&x, &y).unwrap();
+
+        let coef = svc.coef_().unwrap();
+        assert!(coef[0] > 0.0);
+        assert_eq!(&coef[1..], &[0.0, 0.0]);
+        assert_eq!(svc.predict(&x).unwrap(), y);
+    }
+
+    #[test]
+    fn linear_svc_sparse() {
+        let (x, y) = iris_binary();
+        let x_sparse = CsrMatrix::from_dense(&x);
+        let mut dense = LinearSVC::new(parameters(Loss::SquaredHinge, Penalty::L2));
+        dense.fit(&x, &y).unwrap();
+        let mut sparse = LinearSVC::new(parameters(Loss::SquaredHinge, Penalty::L2));
+        sparse.fit_sparse(&x_sparse, &y).unwrap();
+
+        for (d, s) in dense.coef_().unwrap().iter().zip(sparse.coef_().unwrap()) {
+            assert!((d - s).abs() < 1e-9);
+        }
+        assert_eq!(sparse.predict_sparse(&x_sparse).unwrap(), y);
+    }
+
+    #[test]
+    fn linear_svc_errors() {
+        let (x, y) = iris_binary();
+        let mut svc = LinearSVC::new(parameters(Loss::Hinge, Penalty::L1));
+        assert!(matches!(svc.fit(&x, &y), Err(Error::InvalidParameter(_))));
+
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(Box::new(RBFKernel::new(1.0)));
+        let mut svc = LinearSVC::new(parameters);
+        assert!(matches!(svc.fit(&x, &y), Err(Error::InvalidParameter(_))));
+        assert!(matches!(svc.predict(&x), Err(Error::NotFitted)));
+    }
+
+    #[test]
+    fn linear_svc_serialize() {
+        let (x, y) = iris_binary();
+        let mut svc = LinearSVC::new(parameters(Loss::Hinge, Penalty::L2));
+        svc.fit(&x, &y).unwrap();
+
+        let json = serde_json::to_string(&svc).unwrap();
+        let loaded: LinearSVC = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.parameters.loss, Loss::Hinge);
+        assert_eq!(loaded.predict(&x).unwrap(), y);
+    }
+}
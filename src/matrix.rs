@@ -0,0 +1,182 @@
+use crate::{
+    error::{Error, Result},
+    float::Float,
+};
+
+/// Samples as rows of features.
+///
+/// Implemented for slices, arrays and vectors of rows and for [`DenseMatrix`], a view into
+/// a contiguous row-major buffer, so models can be trained and evaluated without copying
+/// the data.
+pub trait Matrix<T: Float = f64>: Sync + Send {
+    fn n_rows(&self) -> usize;
+    fn n_cols(&self) -> usize;
+    /// features of the sample `i`
+    fn row(&self, i: usize) -> &[T];
+}
+
+impl<T: Float> Matrix<T> for [Vec<T>] {
+    fn n_rows(&self) -> usize {
+        self.len()
+    }
+
+    /// length of the first row, rows of other lengths are rejected by the estimators
+    fn n_cols(&self) -> usize {
+        self.first().map_or(0, Vec::len)
+    }
+
+    fn row(&self, i: usize) -> &[T] {
+        &self[i]
+    }
+}
+
+impl<T: Float, const N: usize> Matrix<T> for [Vec<T>; N] {
+    fn n_rows(&self) -> usize {
+        N
+    }
+
+    fn n_cols(&self) -> usize {
+        self.as_slice().n_cols()
+    }
+
+    fn row(&self, i: usize) -> &[T] {
+        &self[i]
+    }
+}
+
+impl<T: Float> Matrix<T> for Vec<Vec<T>> {
+    fn n_rows(&self) -> usize {
+        self.len()
+    }
+
+    fn n_cols(&self) -> usize {
+        self.as_slice().n_cols()
+    }
+
+    fn row(&self, i: usize) -> &[T] {
+        &self[i]
+    }
+}
+
+/// Lets the estimators accept unsized matrices such as `[Vec<T>]` and pass them on as
+/// `&dyn Matrix<T>`.
+impl<T: Float, M: Matrix<T> + ?Sized> Matrix<T> for &M {
+    fn n_rows(&self) -> usize {
+        (**self).n_rows()
+    }
+
+    fn n_cols(&self) -> usize {
+        (**self).n_cols()
+    }
+
+    fn row(&self, i: usize) -> &[T] {
+        (**self).row(i)
+    }
+}
+
+/// Borrowed row-major matrix, row `i` starts at `i * row_stride` in `data`.
+#[derive(Debug, Clone, Copy)]
+pub struct DenseMatrix<'a, T = f64> {
+    data: &'a [T],
+    n_rows: usize,
+    n_cols: usize,
+    row_stride: usize,
+}
+
+impl<'a, T: Float> DenseMatrix<'a, T> {
+    /// Views `data` as a contiguous `n_rows`×`n_cols` matrix.
+    pub fn new(data: &'a [T], n_rows: usize, n_cols: usize) -> Result<Self> {
+        Self::with_row_stride(data, n_rows, n_cols, n_cols)
+    }
+
+    /// Views `data` as a `n_rows`×`n_cols` matrix whose rows are `row_stride` values
+    /// apart, e.g. a block of columns of a larger matrix.
+    pub fn with_row_stride(
+        data: &'a [T],
+        n_rows: usize,
+        n_cols: usize,
+        row_stride: usize,
+    ) -> Result<Self> {
+        if row_stride < n_cols {
+            return Err(Error::DimensionMismatch(format!(
+                "row stride {row_stride} is smaller than the number of columns {n_cols}"
+            )));
+        }
+        let required = match n_rows {
+            0 => 0,
+            n_rows => (n_rows - 1) * row_stride + n_cols,
+        };
+        if data.len() < required {
+            return Err(Error::DimensionMismatch(format!(
+                "{n_rows}×{n_cols} matrix with row stride {row_stride} needs {required} values, got {}",
+                data.len()
+            )));
+        }
+        Ok(DenseMatrix {
+            data,
+            n_rows,
+            n_cols,
+            row_stride,
+        })
+    }
+}
+
+impl<T: Float> Matrix<T> for DenseMatrix<'_, T> {
+    fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    fn row(&self, i: usize) -> &[T] {
+        let begin = i * self.row_stride;
+        &self.data[begin..begin + self.n_cols]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_matrix() {
+        let data = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let matrix = DenseMatrix::new(&data, 3, 2).unwrap();
+
+        assert_eq!(matrix.n_rows(), 3);
+        assert_eq!(matrix.n_cols(), 2);
+        assert_eq!(matrix.row(1), &[3.0, 4.0]);
+        assert!(matches!(
+            DenseMatrix::new(&data, 4, 2),
+            Err(Error::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn strided_matrix() {
+        let data = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let matrix = DenseMatrix::with_row_stride(&data[1..], 2, 2, 3).unwrap();
+
+        assert_eq!(matrix.row(0), &[2.0, 3.0]);
+        assert_eq!(matrix.row(1), &[5.0, 6.0]);
+        assert!(DenseMatrix::with_row_stride(&data, 2, 3, 2).is_err());
+    }
+
+    #[test]
+    fn nested_vec() {
+        let x = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+        let matrix: &dyn Matrix = &x;
+
+        assert_eq!(matrix.n_rows(), 2);
+        assert_eq!(matrix.n_cols(), 2);
+        assert_eq!(matrix.row(1), &[3.0, 4.0]);
+        assert_eq!(Vec::<Vec<f64>>::new().n_cols(), 0);
+
+        let rows = &x[1..];
+        assert_eq!((rows.n_rows(), rows.n_cols()), (1, 2));
+        assert_eq!([vec![5.0, 6.0, 7.0]].n_cols(), 3);
+        assert_eq!((&rows).row(0), &[3.0, 4.0]);
+    }
+}
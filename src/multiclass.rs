@@ -0,0 +1,352 @@
+// source: https://github.com/scikit-learn/scikit-learn/blob/main/sklearn/utils/multiclass.py
+
+#[cfg(feature = "parallel")]
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    matrix::Matrix,
+    parameters::{ClassWeight, Parameters},
+    svc::SVC,
+    svm::SVM,
+    validation,
+};
+
+/// How a multiclass problem is split into binary problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MulticlassStrategy {
+    /// one classifier per pair of classes, the class with the most votes wins
+    OneVsOne,
+    /// one classifier per class against all other classes
+    OneVsRest,
+}
+
+/// Multiclass classifier built from binary [`SVC`]s.
+#[derive(Serialize, Deserialize)]
+pub struct MulticlassSVC {
+    parameters: Parameters,
+    strategy: MulticlassStrategy,
+    classes: Option<Vec<i32>>,
+    estimators: Option<Vec<SVC>>,
+    /// training indices of the samples of each one-vs-one classifier, kept with a
+    /// precomputed kernel to select the columns of the kernel matrix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pair_samples: Option<Vec<Vec<usize>>>,
+    /// number of training samples, the number of columns of a precomputed kernel matrix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    n_samples: Option<usize>,
+}
+
+unsafe impl Sync for MulticlassSVC {}
+unsafe impl Send for MulticlassSVC {}
+
+impl MulticlassSVC {
+    pub fn new(parameters: Parameters, strategy: MulticlassStrategy) -> MulticlassSVC {
+        MulticlassSVC {
+            parameters,
+            strategy,
+            classes: None,
+            estimators: None,
+            pair_samples: None,
+            n_samples: None,
+        }
+    }
+
+    pub fn strategy(&self) -> MulticlassStrategy {
+        self.strategy
+    }
+
+    /// Sorted class labels seen during training.
+    pub fn classes(&self) -> Option<&[i32]> {
+        self.classes.as_deref()
+    }
+
+    /// Index pairs of the classes compared by the one-vs-one classifiers, in the
+    /// order of the classifiers.
+    fn class_pairs(n_classes: usize) -> Vec<(usize, usize)> {
+        (0..n_classes)
+            .flat_map(|i| ((i + 1)..n_classes).map(move |j| (i, j)))
+            .collect()
+    }
+
+    /// Trains a binary classifier, the class weights of the original labels are already
+    /// part of `weights`.
+    fn fit_binary(&self, x: &dyn Matrix, y: &[i32], weights: &[f64]) -> Result<SVC> {
+        let mut parameters = self.parameters.try_clone()?;
+        parameters.with_class_weight(ClassWeight::Uniform);
+        let mut svc = SVC::new(parameters);
+        svc.fit_with_sample_weight(x, y, weights)?;
+        Ok(svc)
+    }
+
+    /// Restricts the columns of a precomputed kernel matrix to the training samples of a
+    /// one-vs-one classifier.
+    fn select_columns(x: &dyn Matrix, samples: &[usize]) -> Vec<Vec<f64>> {
+        (0..x.n_rows())
+            .map(|i| samples.iter().map(|&j| x.row(i)[j]).collect())
+            .collect()
+    }
+
+    /// Computes one decision value per class and sample.
+    ///
+    /// For one-vs-rest these are the decision values of the per-class classifiers. For
+    /// one-vs-one they are the number of votes of each class plus the normalized sum of
+    /// the pairwise decision values, which only breaks ties between votes.
+    ///
+    /// With a precomputed kernel `x` holds the kernel values between the samples and all
+    /// training samples, each one-vs-one classifier only uses the columns of its classes.
+    pub fn decision_function(&self, x: &(impl Matrix + ?Sized)) -> Result<Vec<Vec<f64>>> {
+        let x: &dyn Matrix = &x;
+        let classes = self.classes.as_ref().ok_or(Error::NotFitted)?;
+        let estimators = self.estimators.as_ref().ok_or(Error::NotFitted)?;
+        validation::check_predict_input(x, self.n_samples)?;
+
+        let decide = |(k, svc): (usize, &SVC)| match &self.pair_samples {
+            Some(pair_samples) => svc.decision_function(&Self::select_columns(x, &pair_samples[k])),
+            None => svc.decision_function(x),
+        };
+        #[cfg(feature = "parallel")]
+        let decisions: Vec<Vec<f64>> = estimators
+            .par_iter()
+            .enumerate()
+            .map(decide)
+            .collect::<Result<_>>()?;
+        #[cfg(not(feature = "parallel"))]
+        let decisions: Vec<Vec<f64>> = estimators
+            .iter()
+            .enumerate()
+            .map(decide)
+            .collect::<Result<_>>()?;
+
+        Ok((0..x.n_rows())
+            .map(|sample| match self.strategy {
+                MulticlassStrategy::OneVsRest => {
+                    decisions.iter().map(|decision| decision[sample]).collect()
+                }
+                MulticlassStrategy::OneVsOne => {
+                    let mut votes = vec![0.0; classes.len()];
+                    let mut confidences = vec![0.0; classes.len()];
+                    for ((i, j), decision) in Self::class_pairs(classes.len())
+                        .into_iter()
+                        .zip(decisions.iter())
+                    {
+                        let d = decision[sample];
+                        if d > 0.0 {
+                            votes[i] += 1.0;
+                        } else {
+                            votes[j] += 1.0;
+                        }
+                        confidences[i] += d;
+                        confidences[j] -= d;
+                    }
+                    votes
+                        .iter()
+                        .zip(confidences.iter())
+                        .map(|(vote, confidence)| {
+                            vote + confidence / (3.0 * (confidence.abs() + 1.0))
+                        })
+                        .collect()
+                }
+            })
+            .collect())
+    }
+}
+
+impl SVM for MulticlassSVC {
+    fn fit(&mut self, x: &(impl Matrix + ?Sized), y: &[i32]) -> Result<()> {
+        let x: &dyn Matrix = &x;
+        let precomputed = self.parameters.precomputed;
+        validation::check_fit_input(x, Some(y.len()), precomputed)?;
+
+        let mut classes = y.to_vec();
+        classes.sort_unstable();
+        classes.dedup();
+        if classes.len() < 2 {
+            return Err(Error::InvalidLabel(format!(
+                "at least two classes are required, got {}",
+                classes.len()
+            )));
+        }
+        let weights = self.parameters.class_weight.sample_weights(y);
+
+        let mut pair_samples: Vec<Vec<usize>> = Vec::new();
+        let estimators = match self.strategy {
+            MulticlassStrategy::OneVsRest => classes
+                .iter()
+                .map(|&class| {
+                    let y_binary: Vec<i32> = y
+                        .iter()
+                        .map(|&label| if label == class { 1 } else { -1 })
+                        .collect();
+                    self.fit_binary(x, &y_binary, &weights)
+                })
+                .collect::<Result<_>>()?,
+            MulticlassStrategy::OneVsOne => {
+                let pairs = Self::class_pairs(classes.len());
+                pair_samples = pairs
+                    .iter()
+                    .map(|&(i, j)| {
+                        (0..y.len())
+                            .filter(|&k| y[k] == classes[i] || y[k] == classes[j])
+                            .collect()
+                    })
+                    .collect();
+                pairs
+                    .iter()
+                    .zip(pair_samples.iter())
+                    .map(|(&(i, _), pair)| {
+                        // with a precomputed kernel the columns are restricted to the
+                        // samples of the pair as well
+                        let x_pair: Vec<Vec<f64>> = pair
+                            .iter()
+                            .map(|&k| {
+                                if precomputed {
+                                    pair.iter().map(|&l| x.row(k)[l]).collect()
+                                } else {
+                                    x.row(k).to_vec()
+                                }
+                            })
+                            .collect();
+                        let y_pair: Vec<i32> = pair
+                            .iter()
+                            .map(|&k| if y[k] == classes[i] { 1 } else { -1 })
+                            .collect();
+                        let weights_pair: Vec<f64> = pair.iter().map(|&k| weights[k]).collect();
+                        self.fit_binary(&x_pair, &y_pair, &weights_pair)
+                    })
+                    .collect::<Result<_>>()?
+            }
+        };
+
+        self.classes = Some(classes);
+        self.estimators = Some(estimators);
+        let pair_precomputed = precomputed && self.strategy == MulticlassStrategy::OneVsOne;
+        self.pair_samples = pair_precomputed.then_some(pair_samples);
+        self.n_samples = precomputed.then_some(y.len());
+        Ok(())
+    }
+
+    fn predict(&self, x: &(impl Matrix + ?Sized)) -> Result<Vec<i32>> {
+        let classes = self.classes.as_ref().ok_or(Error::NotFitted)?;
+        Ok(self
+            .decision_function(x)?
+            .iter()
+            .map(|scores| {
+                let best =
+                    scores.iter().enumerate().fold(
+                        0,
+                        |best, (i, score)| if *score > scores[best] { i } else { best },
+                    );
+                classes[best]
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kernel::{Kernel, RBFKernel},
+        test_utils::iris,
+    };
+
+    fn accuracy(predictions: &[i32], y: &[i32]) -> f64 {
+        let correct = predictions
+            .iter()
+            .zip(y.iter())
+            .filter(|(pred, label)| pred == label)
+            .count();
+        correct as f64 / y.len() as f64
+    }
+
+    #[test]
+    fn one_vs_one() {
+        let (x, y) = iris();
+        let y: Vec<i32> = y.iter().map(|&label| [3, 7, 9][label as usize]).collect();
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(Box::new(RBFKernel::new(0.5)));
+        let mut svc = MulticlassSVC::new(parameters, MulticlassStrategy::OneVsOne);
+
+        svc.fit(&x, &y).unwrap();
+
+        assert_eq!(svc.classes(), Some(&[3, 7, 9][..]));
+        assert_eq!(svc.decision_function(&x).unwrap()[0].len(), 3);
+        let accuracy = accuracy(&svc.predict(&x).unwrap(), &y);
+        assert!(
+            accuracy >= 0.9,
+            "Accuracy ({accuracy}) is not larger or equal to 0.9"
+        );
+    }
+
+    #[test]
+    fn one_vs_rest() {
+        let (x, y) = iris();
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(Box::new(RBFKernel::new(0.5)));
+        let mut svc = MulticlassSVC::new(parameters, MulticlassStrategy::OneVsRest);
+
+        svc.fit(&x, &y).unwrap();
+
+        assert_eq!(svc.decision_function(&x).unwrap()[0].len(), 3);
+        let accuracy = accuracy(&svc.predict(&x).unwrap(), &y);
+        assert!(
+            accuracy >= 0.9,
+            "Accuracy ({accuracy}) is not larger or equal to 0.9"
+        );
+    }
+
+    #[test]
+    fn one_vs_one_precomputed() {
+        let (x, y) = iris();
+        let kernel = RBFKernel::new(0.5);
+        let gram: Vec<Vec<f64>> = x
+            .iter()
+            .map(|x_i| x.iter().map(|x_j| kernel.compute(x_i, x_j)).collect())
+            .collect();
+
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(Box::new(RBFKernel::new(0.5)));
+        let mut svc = MulticlassSVC::new(parameters, MulticlassStrategy::OneVsOne);
+        svc.fit(&x, &y).unwrap();
+
+        let mut parameters = Parameters::default();
+        parameters.with_precomputed(true);
+        let mut precomputed = MulticlassSVC::new(parameters, MulticlassStrategy::OneVsOne);
+        precomputed.fit(&gram, &y).unwrap();
+
+        let expected = svc.decision_function(&x).unwrap();
+        let result = precomputed.decision_function(&gram).unwrap();
+        for (expected, result) in expected.iter().flatten().zip(result.iter().flatten()) {
+            assert!((expected - result).abs() < 1e-9);
+        }
+        assert_eq!(
+            precomputed.predict(&gram).unwrap(),
+            svc.predict(&x).unwrap()
+        );
+
+        let json = serde_json::to_string(&precomputed).unwrap();
+        let loaded: MulticlassSVC = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.predict(&gram).unwrap(), svc.predict(&x).unwrap());
+
+        let narrow: Vec<Vec<f64>> = gram.iter().map(|row| row[..20].to_vec()).collect();
+        assert!(matches!(
+            precomputed.decision_function(&narrow),
+            Err(Error::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn serialize() {
+        let (x, y) = iris();
+        let mut svc = MulticlassSVC::new(Parameters::default(), MulticlassStrategy::OneVsOne);
+        svc.fit(&x, &y).unwrap();
+
+        let json = serde_json::to_string(&svc).unwrap();
+        let loaded: MulticlassSVC = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.strategy(), MulticlassStrategy::OneVsOne);
+        assert_eq!(loaded.predict(&x).unwrap(), svc.predict(&x).unwrap());
+    }
+}
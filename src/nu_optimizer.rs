@@ -0,0 +1,397 @@
+// source: https://www.csie.ntu.edu.tw/~cjlin/papers/libsvm.pdf
+// source: https://github.com/cjlin1/libsvm/blob/master/svm.cpp
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    cache::{Cache, CacheStats, DEFAULT_CACHE_SIZE},
+    error::{Error, Result},
+    float::Float,
+    kernel::KernelSource,
+    optimizer::{Convergence, ConvergenceStatus, Progress, ProgressHook, Signal},
+};
+
+const TAU: f64 = 1e-12;
+
+/// Solver for the nu-SVC dual problem.
+///
+/// Solves `min 1/2 sum_ij alpha_i alpha_j y_i y_j K_ij` subject to `0 <= alpha_i <= 1`,
+/// `sum_i y_i alpha_i = 0` and `sum_i alpha_i = nu * n`. Both equality constraints are
+/// kept by only updating pairs of samples of the same class.
+///
+/// Training goes through [`SVC`](crate::SVC) with `Formulation::Nu`.
+#[derive(Debug)]
+pub(crate) struct NuSMO<T: Float = f64> {
+    /// bound on the fraction of margin errors and support vectors
+    nu: f64,
+    /// numerical tolerance
+    tol: f64,
+    /// maximum number of pair updates, `max(10^7, 100 n)` if not set
+    max_iter: Option<usize>,
+    /// maximum training time
+    max_time: Option<Duration>,
+    progress: Option<ProgressHook>,
+    /// memory budget of the kernel cache in bytes
+    cache_size: usize,
+
+    cache: Cache<T>,
+    convergence: Option<Convergence>,
+}
+
+impl<T: Float> NuSMO<T> {
+    pub(crate) fn new(nu: f64, tol: f64) -> Self {
+        NuSMO {
+            nu,
+            tol,
+            max_iter: None,
+            max_time: None,
+            progress: None,
+            cache_size: DEFAULT_CACHE_SIZE,
+            cache: Cache::new(),
+            convergence: None,
+        }
+    }
+
+    pub(crate) fn with_max_iter(&mut self, max_iter: Option<usize>) -> &mut Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub(crate) fn with_max_time(&mut self, max_time: Option<Duration>) -> &mut Self {
+        self.max_time = max_time;
+        self
+    }
+
+    pub(crate) fn with_cache_size(&mut self, cache_size: usize) -> &mut Self {
+        self.cache_size = cache_size;
+        self
+    }
+
+    /// Lookups of the kernel cache during the last optimization.
+    pub(crate) fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Reports the progress to `progress` every few iterations, the hook can cancel the
+    /// optimization. The solver has no epochs, the reported epoch is always 0.
+    pub(crate) fn with_progress(&mut self, progress: Option<ProgressHook>) -> &mut Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Removes the progress hook, e.g. to reuse it after the optimization.
+    pub(crate) fn take_progress(&mut self) -> Option<ProgressHook> {
+        self.progress.take()
+    }
+}
+
+impl<T: Float> NuSMO<T> {
+    fn kernel(&mut self, source: &dyn KernelSource<T>, i: usize, j: usize) -> T {
+        self.cache.get_or_compute(i, j, || source.compute(i, j))
+    }
+
+    /// Returns the KKT violation and the pair with the largest second order gain, or
+    /// `None` if the KKT conditions hold within the tolerance.
+    fn select_working_set(
+        &mut self,
+        source: &dyn KernelSource<T>,
+        y: &[T],
+        alpha: &[T],
+        grad: &[T],
+        diag: &[T],
+    ) -> (T, Option<(usize, usize)>) {
+        let (zero, one) = (T::zero(), T::one());
+        let n = y.len();
+        let mut gmax_p = T::neg_infinity();
+        let mut gmax_p2 = T::neg_infinity();
+        let mut gmax_p_idx = None;
+        let mut gmax_n = T::neg_infinity();
+        let mut gmax_n2 = T::neg_infinity();
+        let mut gmax_n_idx = None;
+
+        for t in 0..n {
+            if y[t] > zero {
+                if alpha[t] < one && -grad[t] >= gmax_p {
+                    gmax_p = -grad[t];
+                    gmax_p_idx = Some(t);
+                }
+            } else if alpha[t] > zero && grad[t] >= gmax_n {
+                gmax_n = grad[t];
+                gmax_n_idx = Some(t);
+            }
+        }
+
+        let mut gmin_idx = None;
+        let mut obj_diff_min = T::infinity();
+
+        for j in 0..n {
+            let (i, grad_diff) = if y[j] > zero {
+                if alpha[j] <= zero {
+                    continue;
+                }
+                gmax_p2 = gmax_p2.max(grad[j]);
+                (gmax_p_idx, gmax_p + grad[j])
+            } else {
+                if alpha[j] >= one {
+                    continue;
+                }
+                gmax_n2 = gmax_n2.max(-grad[j]);
+                (gmax_n_idx, gmax_n - grad[j])
+            };
+            let Some(i) = i else {
+                continue;
+            };
+            if grad_diff > zero {
+                let mut quad_coef = diag[i] + diag[j] - T::cast(2.0) * self.kernel(source, i, j);
+                if quad_coef <= zero {
+                    quad_coef = T::cast(TAU);
+                }
+                let obj_diff = -(grad_diff * grad_diff) / quad_coef;
+                if obj_diff <= obj_diff_min {
+                    gmin_idx = Some(j);
+                    obj_diff_min = obj_diff;
+                }
+            }
+        }
+
+        let violation = (gmax_p + gmax_p2).max(gmax_n + gmax_n2);
+        if violation < T::cast(self.tol) {
+            return (violation, None);
+        }
+        let pair = gmin_idx.and_then(|j| {
+            let i = if y[j] > zero { gmax_p_idx } else { gmax_n_idx };
+            i.map(|i| (i, j))
+        });
+        (violation, pair)
+    }
+
+    /// Average gradient of the free variables of one class, or the middle of the
+    /// feasible interval if no variable is free.
+    fn class_offset(y: &[T], alpha: &[T], grad: &[T], class: T) -> T {
+        let mut upper = T::infinity();
+        let mut lower = T::neg_infinity();
+        let mut n_free = 0;
+        let mut sum_free = T::zero();
+        for i in (0..y.len()).filter(|&i| y[i] == class) {
+            if alpha[i] >= T::one() {
+                lower = lower.max(grad[i]);
+            } else if alpha[i] <= T::zero() {
+                upper = upper.min(grad[i]);
+            } else {
+                n_free += 1;
+                sum_free += grad[i];
+            }
+        }
+        if n_free > 0 {
+            sum_free / T::cast(n_free as f64)
+        } else {
+            (upper + lower) / T::cast(2.0)
+        }
+    }
+}
+
+impl<T: Float> NuSMO<T> {
+    /// Returns the training indices of the support vectors, their weights and the bias.
+    ///
+    /// Fails if `nu` is infeasible for the class sizes or the solution is degenerate.
+    pub(crate) fn optimize(
+        &mut self,
+        source: &dyn KernelSource<T>,
+        y: &[i32],
+    ) -> Result<(Vec<usize>, Vec<T>, T)> {
+        let (zero, one, two) = (T::zero(), T::one(), T::cast(2.0));
+        let n = source.n_samples();
+        let nu = self.nu;
+        let positives = y.iter().filter(|&&y_i| y_i > 0).count();
+        let smallest = positives.min(n - positives);
+        if nu * n as f64 > 2.0 * smallest as f64 {
+            return Err(Error::InvalidParameter(format!(
+                "nu = {nu} is infeasible for {smallest} samples of the smallest class"
+            )));
+        }
+        let y: Vec<T> = y
+            .iter()
+            .map(|&y_i| if y_i > 0 { one } else { -one })
+            .collect();
+        self.cache = Cache::with_capacity(self.cache_size);
+
+        // feasible starting point with nu * n / 2 on each class
+        let mut sum_pos = T::cast(nu * n as f64 / 2.0);
+        let mut sum_neg = sum_pos;
+        let mut alpha = vec![zero; n];
+        for i in 0..n {
+            let sum = if y[i] > zero {
+                &mut sum_pos
+            } else {
+                &mut sum_neg
+            };
+            alpha[i] = sum.min(one);
+            *sum -= alpha[i];
+        }
+
+        let diag: Vec<T> = (0..n).map(|i| source.compute(i, i)).collect();
+
+        // gradient of the objective, (Q alpha)_i
+        let mut grad = vec![zero; n];
+        for j in (0..n).filter(|&j| alpha[j] > zero) {
+            for i in 0..n {
+                grad[i] += alpha[j] * y[i] * y[j] * self.kernel(source, i, j);
+            }
+        }
+
+        // the clock is only read if needed, it is not available on every target
+        let start = (self.max_time.is_some() || self.progress.is_some()).then(Instant::now);
+        let deadline = start
+            .zip(self.max_time)
+            .map(|(start, max_time)| start + max_time);
+        let mut status = ConvergenceStatus::IterationLimit;
+        let mut iterations = 0;
+        let mut kkt_violation = zero;
+        let max_iter = self.max_iter.unwrap_or(10_000_000.max(100 * n));
+        while iterations < max_iter {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                status = ConvergenceStatus::TimeLimit;
+                break;
+            }
+            let (violation, pair) = self.select_working_set(source, &y, &alpha, &grad, &diag);
+            kkt_violation = violation;
+            let Some((i, j)) = pair else {
+                status = ConvergenceStatus::Converged;
+                break;
+            };
+            iterations += 1;
+
+            let mut quad_coef = diag[i] + diag[j] - two * self.kernel(source, i, j);
+            if quad_coef <= zero {
+                quad_coef = T::cast(TAU);
+            }
+            let delta = (grad[i] - grad[j]) / quad_coef;
+            let sum = alpha[i] + alpha[j];
+            let (old_i, old_j) = (alpha[i], alpha[j]);
+            let mut alpha_i = old_i - delta;
+            let mut alpha_j = old_j + delta;
+            if sum > one {
+                if alpha_i > one {
+                    alpha_i = one;
+                    alpha_j = sum - one;
+                }
+                if alpha_j > one {
+                    alpha_j = one;
+                    alpha_i = sum - one;
+                }
+            } else {
+                if alpha_j < zero {
+                    alpha_j = zero;
+                    alpha_i = sum;
+                }
+                if alpha_i < zero {
+                    alpha_i = zero;
+                    alpha_j = sum;
+                }
+            }
+            alpha[i] = alpha_i;
+            alpha[j] = alpha_j;
+
+            let (delta_i, delta_j) = (alpha_i - old_i, alpha_j - old_j);
+            for k in 0..n {
+                let q_ik = y[i] * y[k] * self.kernel(source, i, k);
+                let q_jk = y[j] * y[k] * self.kernel(source, j, k);
+                grad[k] += q_ik * delta_i + q_jk * delta_j;
+            }
+
+            if let Some(progress) = self.progress.as_mut() {
+                let signal = progress.report(iterations, || Progress {
+                    epoch: 0,
+                    iterations,
+                    n_support: alpha.iter().filter(|&&alpha_i| alpha_i > zero).count(),
+                    gradient_gap: kkt_violation.as_f64(),
+                    elapsed: start.map(|start| start.elapsed()),
+                });
+                if signal == Signal::Cancel {
+                    status = ConvergenceStatus::Cancelled;
+                    break;
+                }
+            }
+        }
+
+        let r1 = Self::class_offset(&y, &alpha, &grad, one);
+        let r2 = Self::class_offset(&y, &alpha, &grad, -one);
+        let r = (r1 + r2) / two;
+        let rho = (r1 - r2) / two;
+        self.convergence = Some(Convergence {
+            status,
+            iterations,
+            kkt_violation: kkt_violation.max(zero).as_f64(),
+        });
+        // the weights are scaled by 1 / r, e.g. samples that all have the same kernel
+        // values leave no margin to scale by
+        if !(r > zero && r.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "nu = {nu} gives a degenerate solution without margin (r = {r})"
+            )));
+        }
+
+        let mut support = Vec::new();
+        let mut w = Vec::new();
+        for i in (0..n).filter(|&i| alpha[i] > zero) {
+            support.push(i);
+            w.push(y[i] * alpha[i] / r);
+        }
+        Ok((support, w, -rho / r))
+    }
+
+    /// How the last call of [`NuSMO::optimize`] stopped, `None` before the first call.
+    pub(crate) fn convergence(&self) -> Option<Convergence> {
+        self.convergence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{FeatureKernelSource, RBFKernel};
+
+    #[test]
+    fn nu_bounds_support_vectors() {
+        let x: Vec<Vec<f64>> = (0..40)
+            .map(|i| vec![(i % 20) as f64 * 0.1 + if i < 20 { 0.0 } else { 1.5 }])
+            .collect();
+        let y: Vec<i32> = (0..40).map(|i| if i < 20 { -1 } else { 1 }).collect();
+        let kernel = RBFKernel::new(1.0);
+        let source = FeatureKernelSource::new(&x, &kernel);
+
+        for nu in [0.2, 0.5, 0.9] {
+            let (support, _, _) = NuSMO::new(nu, 1e-3).optimize(&source, &y).unwrap();
+            assert!(
+                support.len() as f64 >= nu * x.len() as f64 - 1e-9,
+                "{} support vectors for nu = {nu}",
+                support.len()
+            );
+        }
+        // the classes share the samples from 1.5 to 1.9, a small nu leaves no margin
+        assert!(matches!(
+            NuSMO::new(0.1, 1e-3).optimize(&source, &y),
+            Err(Error::InvalidParameter(message)) if message.contains("degenerate")
+        ));
+
+        // 10 negative and 20 positive samples
+        let x = &x[10..];
+        let source = FeatureKernelSource::new(&x, &kernel);
+        assert!(matches!(
+            NuSMO::new(0.8, 1e-3).optimize(&source, &y[10..]),
+            Err(Error::InvalidParameter(message)) if message.contains("infeasible")
+        ));
+    }
+
+    #[test]
+    fn nu_degenerate_solution() {
+        // every kernel value is 1, all gradients vanish and there is no margin
+        let x = vec![vec![1.0, 2.0]; 4];
+        let kernel = RBFKernel::new(1.0);
+        let source = FeatureKernelSource::new(&x, &kernel);
+        assert!(matches!(
+            NuSMO::new(0.5, 1e-3).optimize(&source, &[1, 1, -1, -1]),
+            Err(Error::InvalidParameter(message)) if message.contains("degenerate")
+        ));
+    }
+}
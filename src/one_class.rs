@@ -0,0 +1,290 @@
+// source: https://www.csie.ntu.edu.tw/~cjlin/papers/libsvm.pdf
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    kernel::{FeatureKernelSource, KernelSource, PrecomputedKernelSource},
+    matrix::Matrix,
+    optimizer::{Convergence, ProgressHook},
+    parameters::Parameters,
+    smartcore_optimizer::SMO,
+    svc::SVC,
+    validation, B, W,
+};
+
+/// One-class support vector machine for novelty and outlier detection.
+///
+/// Solves `min 1/2 sum_ij alpha_i alpha_j K_ij` subject to `0 <= alpha_i <= 1` and
+/// `sum_i alpha_i = nu * n`, so `nu` bounds the fraction of outliers from above and
+/// the fraction of support vectors from below.
+#[derive(Serialize, Deserialize)]
+pub struct OneClassSVM {
+    parameters: Parameters,
+    support_vectors: Option<Vec<Vec<f64>>>,
+    /// training indices of the support vectors
+    support: Option<Vec<usize>>,
+    w: Option<W>,
+    b: Option<B>,
+    /// number of features, or of training samples with a precomputed kernel
+    n_features: Option<usize>,
+    /// how the optimizer stopped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    convergence: Option<Convergence>,
+    #[serde(skip)]
+    progress: Option<ProgressHook>,
+}
+
+unsafe impl Sync for OneClassSVM {}
+unsafe impl Send for OneClassSVM {}
+
+impl OneClassSVM {
+    pub fn new(parameters: Parameters) -> OneClassSVM {
+        OneClassSVM {
+            parameters,
+            support_vectors: None,
+            support: None,
+            w: None,
+            b: None,
+            n_features: None,
+            convergence: None,
+            progress: None,
+        }
+    }
+
+    fn is_precomputed(&self) -> bool {
+        self.parameters.precomputed
+    }
+
+    /// Reports the training progress to `progress`, the hook can cancel the training.
+    ///
+    /// A cancelled model keeps the solution found so far.
+    pub fn with_progress(&mut self, progress: ProgressHook) -> &mut Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// How the optimizer stopped during training.
+    pub fn convergence(&self) -> Option<Convergence> {
+        self.convergence
+    }
+
+    /// Solves the one-class problem, `progress` is handed to the optimizer and returned
+    /// afterwards.
+    fn optimize(
+        parameters: &Parameters,
+        progress: &mut Option<ProgressHook>,
+        source: &dyn KernelSource,
+    ) -> ((Vec<usize>, Vec<f64>, f64), Option<Convergence>) {
+        let n = source.n_samples();
+        let nu = parameters.nu;
+
+        // feasible starting point: the first floor(nu * n) alphas at the upper bound
+        let total = nu * n as f64;
+        let alphas: Vec<f64> = (0..n).map(|i| (total - i as f64).clamp(0.0, 1.0)).collect();
+
+        let mut smo = SMO::new(1.0, parameters.tol, parameters.epochs);
+        smo.with_seed(Some(100))
+            .with_max_iter(parameters.max_iter)
+            .with_max_time(parameters.max_time)
+            .with_cache_size(parameters.cache_size)
+            .with_progress(progress.take());
+        let result =
+            smo.optimize_problem_from(source, &vec![0.0; n], &vec![(0.0, 1.0); n], &alphas);
+        *progress = smo.take_progress();
+        (result, smo.convergence())
+    }
+
+    /// Trains the model on unlabeled samples. With a precomputed kernel `x` is the n×n
+    /// Gram matrix of the training samples.
+    pub fn fit(&mut self, x: &(impl Matrix + ?Sized)) -> Result<()> {
+        let x: &dyn Matrix = &x;
+        validation::check_nu(self.parameters.nu)?;
+        let precomputed = self.is_precomputed();
+        let n_features = validation::check_fit_input(x, None, precomputed)?;
+
+        let source: Box<dyn KernelSource> = if precomputed {
+            Box::new(PrecomputedKernelSource::new(x))
+        } else {
+            Box::new(FeatureKernelSource::new(x, &self.parameters.kernel))
+        };
+        let ((support, w, b), convergence) =
+            Self::optimize(&self.parameters, &mut self.progress, source.as_ref());
+
+        self.support_vectors = if precomputed {
+            None
+        } else {
+            Some(support.iter().map(|&i| x.row(i).to_vec()).collect())
+        };
+        self.support = Some(support);
+        self.w = Some(w);
+        self.b = Some(b);
+        self.n_features = Some(n_features);
+        self.convergence = convergence;
+        Ok(())
+    }
+
+    /// Computes the signed distance to the separating hyperplane, positive for inliers.
+    ///
+    /// With a precomputed kernel `x` is the m×n kernel matrix between the m test samples
+    /// and the n training samples.
+    pub fn decision_function(&self, x: &(impl Matrix + ?Sized)) -> Result<Vec<f64>> {
+        let x: &dyn Matrix = &x;
+        let w = self.w.as_ref().ok_or(Error::NotFitted)?;
+        let b = self.b.ok_or(Error::NotFitted)?;
+        validation::check_predict_input(x, self.n_features)?;
+
+        if self.is_precomputed() {
+            let support = self.support.as_ref().ok_or(Error::NotFitted)?;
+            return Ok((0..x.n_rows())
+                .map(|i| SVC::predict_precomputed_row(x.row(i), w, support, b))
+                .collect());
+        }
+
+        let support_vectors = self.support_vectors.as_ref().ok_or(Error::NotFitted)?;
+
+        #[cfg(feature = "parallel")]
+        let y: Vec<f64> = (0..x.n_rows())
+            .into_par_iter()
+            .map(|i| SVC::predict_row(x.row(i), w, support_vectors, b, &self.parameters.kernel))
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let y: Vec<f64> = (0..x.n_rows())
+            .map(|i| SVC::predict_row(x.row(i), w, support_vectors, b, &self.parameters.kernel))
+            .collect();
+        Ok(y)
+    }
+
+    /// Returns `1` for inliers and `-1` for outliers.
+    pub fn predict(&self, x: &(impl Matrix + ?Sized)) -> Result<Vec<i32>> {
+        Ok(self
+            .decision_function(x)?
+            .iter()
+            .map(|&y| if y >= 0.0 { 1 } else { -1 })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        kernel::RBFKernel,
+        optimizer::{ConvergenceStatus, Progress, Signal},
+    };
+
+    fn cluster() -> Vec<Vec<f64>> {
+        (0..30)
+            .map(|i| {
+                let angle = i as f64 * 0.7;
+                let radius = 0.2 + 0.02 * (i % 5) as f64;
+                vec![radius * angle.cos(), radius * angle.sin()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn one_class_fit_predict() {
+        let x = cluster();
+
+        let mut parameters = Parameters::default();
+        parameters
+            .with_kernel(Box::new(RBFKernel::new(2.0)))
+            .with_nu(0.1);
+        let mut model = OneClassSVM::new(parameters);
+
+        model.fit(&x).unwrap();
+
+        let inliers = model
+            .predict(&x)
+            .unwrap()
+            .iter()
+            .filter(|&&y| y == 1)
+            .count();
+        assert!(
+            inliers >= 24,
+            "Only {inliers} of 30 training samples are inliers"
+        );
+
+        let outliers = vec![vec![3.0, 3.0], vec![-4.0, 2.0], vec![0.0, -5.0]];
+        assert_eq!(model.predict(&outliers).unwrap(), vec![-1, -1, -1]);
+        assert_eq!(model.predict(&[vec![0.0, 0.0]]).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn one_class_serialize() {
+        let x = cluster();
+        let mut model = OneClassSVM::new(Parameters::default());
+        model.fit(&x).unwrap();
+
+        let json = serde_json::to_string(&model).unwrap();
+        let loaded: OneClassSVM = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            loaded.decision_function(&x).unwrap(),
+            model.decision_function(&x).unwrap()
+        );
+    }
+
+    #[test]
+    fn one_class_convergence() {
+        let x = cluster();
+
+        let mut model = OneClassSVM::new(Parameters::default());
+        model.fit(&x).unwrap();
+        let convergence = model.convergence().unwrap();
+        assert_eq!(convergence.status, ConvergenceStatus::Converged);
+        assert!(convergence.iterations > 0);
+        assert!(convergence.kkt_violation <= 1e-3);
+
+        let mut parameters = Parameters::default();
+        parameters.with_max_iter(1);
+        let mut model = OneClassSVM::new(parameters);
+        model.fit(&x).unwrap();
+        let convergence = model.convergence().unwrap();
+        assert_eq!(convergence.status, ConvergenceStatus::IterationLimit);
+        assert_eq!(convergence.iterations, 1);
+
+        let mut parameters = Parameters::default();
+        parameters.with_max_time(Duration::ZERO);
+        let mut model = OneClassSVM::new(parameters);
+        model.fit(&x).unwrap();
+        assert_eq!(
+            model.convergence().unwrap().status,
+            ConvergenceStatus::TimeLimit
+        );
+        assert_eq!(model.predict(&x).unwrap().len(), x.len());
+    }
+
+    #[test]
+    fn one_class_progress() {
+        let x = cluster();
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::<Progress>::new()));
+        let seen = reports.clone();
+        let mut parameters = Parameters::default();
+        parameters.with_nu(0.5);
+        let mut model = OneClassSVM::new(parameters);
+        model.with_progress(ProgressHook::new(2, move |progress| {
+            seen.lock().unwrap().push(*progress);
+            if progress.iterations >= 4 {
+                Signal::Cancel
+            } else {
+                Signal::Continue
+            }
+        }));
+        model.fit(&x).unwrap();
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(
+            reports.iter().map(|p| p.iterations).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        let convergence = model.convergence().unwrap();
+        assert_eq!(convergence.status, ConvergenceStatus::Cancelled);
+        assert_eq!(convergence.iterations, 4);
+        assert_eq!(model.predict(&x).unwrap().len(), x.len());
+    }
+}
@@ -1,12 +1,101 @@
-use crate::Kernel;
+use std::{fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{float::Float, kernel::KernelSource};
 
 pub type AlphasB = (Vec<f64>, f64);
 
-pub trait Optimizer {
-    fn optimize(
+pub trait Optimizer<T: Float = f64> {
+    /// Returns the training indices of the support vectors, their weights and the bias.
+    fn optimize(&mut self, source: &dyn KernelSource<T>, y: &[i32]) -> (Vec<usize>, Vec<T>, T);
+
+    /// How the last call of [`Optimizer::optimize`] stopped, `None` before the first call.
+    fn convergence(&self) -> Option<Convergence> {
+        None
+    }
+}
+
+/// Why an optimizer stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConvergenceStatus {
+    /// the KKT conditions hold within the tolerance
+    Converged,
+    /// the maximum number of iterations was reached or the optimizer stopped making
+    /// progress before reaching the tolerance
+    IterationLimit,
+    /// the time budget was used up
+    TimeLimit,
+    /// a [`ProgressHook`] cancelled the training
+    Cancelled,
+}
+
+/// Result of a training run.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Convergence {
+    pub status: ConvergenceStatus,
+    /// number of pair updates
+    pub iterations: usize,
+    /// largest violation of the KKT conditions, `max_grad - min_grad`
+    pub kkt_violation: f64,
+}
+
+/// State of a running optimizer passed to a [`ProgressHook`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// current pass over the samples
+    pub epoch: usize,
+    /// number of pair updates so far
+    pub iterations: usize,
+    /// number of active support vectors
+    pub n_support: usize,
+    /// current KKT violation `max_grad - min_grad`
+    pub gradient_gap: f64,
+    /// time since the training started, `None` if the optimizer did not read the clock
+    pub elapsed: Option<Duration>,
+}
+
+/// Returned by a [`ProgressHook`] to continue or stop the training.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Continue,
+    /// stops the optimizer, the model keeps the solution found so far
+    Cancel,
+}
+
+/// Callback invoked every `every` iterations of an optimizer.
+pub struct ProgressHook {
+    every: usize,
+    callback: Box<dyn FnMut(&Progress) -> Signal + Send>,
+}
+
+impl ProgressHook {
+    pub fn new(every: usize, callback: impl FnMut(&Progress) -> Signal + Send + 'static) -> Self {
+        ProgressHook {
+            every: every.max(1),
+            callback: Box::new(callback),
+        }
+    }
+
+    /// Calls the callback if `iterations` is a multiple of `every`, the progress is only
+    /// collected then.
+    pub(crate) fn report(
         &mut self,
-        x: &Vec<Vec<f64>>,
-        y: &Vec<i32>,
-        kernel: &Box<dyn Kernel>,
-    ) -> (Vec<Vec<f64>>, Vec<f64>, f64);
+        iterations: usize,
+        progress: impl FnOnce() -> Progress,
+    ) -> Signal {
+        if iterations.is_multiple_of(self.every) {
+            (self.callback)(&progress())
+        } else {
+            Signal::Continue
+        }
+    }
+}
+
+impl fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressHook")
+            .field("every", &self.every)
+            .finish_non_exhaustive()
+    }
 }
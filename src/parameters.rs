@@ -1,27 +1,153 @@
+use std::{collections::HashMap, time::Duration};
+
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
-    kernel::{self, KernelType},
+    cache::DEFAULT_CACHE_SIZE,
+    float::Float,
+    kernel::{self, KernelError, KernelType},
     Kernel,
 };
 
 #[derive(Serialize, Deserialize)]
-pub struct Parameters {
+#[serde(bound = "T: Float")]
+pub struct Parameters<T: Float = f64> {
     /// Kernel
     #[serde(
         serialize_with = "serialize_kernel",
         deserialize_with = "deserialize_kernel"
     )]
-    pub kernel: Box<dyn Kernel>,
+    pub kernel: Box<dyn Kernel<T>>,
+    /// the samples are rows of a precomputed kernel matrix, `kernel` is not evaluated
+    #[serde(default)]
+    pub precomputed: bool,
     /// regularization parameter
     pub c: f64,
     /// numerical tolerance
     pub tol: f64,
     /// maximum number of iterations over Larange multipliers without changing
     pub epochs: usize,
+    /// width of the insensitive tube of the regression loss
+    #[serde(default = "default_epsilon")]
+    pub epsilon: f64,
+    /// bound on the fraction of margin errors and support vectors
+    #[serde(default = "default_nu")]
+    pub nu: f64,
+    /// classification problem solved by `SVC`
+    #[serde(default)]
+    pub formulation: Formulation,
+    /// fit Platt scaling for probability estimates during training
+    #[serde(default)]
+    pub probability: bool,
+    /// scales `c` per class
+    #[serde(default)]
+    pub class_weight: ClassWeight,
+    /// loss of the margin violations minimized by `LinearSVC`
+    #[serde(default)]
+    pub loss: Loss,
+    /// regularization of the weights of `LinearSVC`
+    #[serde(default)]
+    pub penalty: Penalty,
+    /// maximum number of iterations of the optimizers, passes over the data for
+    /// `LinearSVC`
+    #[serde(default)]
+    pub max_iter: Option<usize>,
+    /// maximum training time of the optimizers
+    #[serde(default)]
+    pub max_time: Option<Duration>,
+    /// memory budget of the kernel cache in bytes
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+}
+
+/// Formulation of the classification problem.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Formulation {
+    /// C-SVC, `c` penalizes margin errors
+    #[default]
+    C,
+    /// nu-SVC, `nu` bounds the fraction of margin errors and support vectors
+    Nu,
+}
+
+/// Loss of a sample with margin `m = y * f(x)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Loss {
+    /// L1-loss `max(0, 1 - m)`
+    Hinge,
+    /// L2-loss `max(0, 1 - m)^2`
+    #[default]
+    SquaredHinge,
+}
+
+/// Regularization of the weight vector.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Penalty {
+    /// `||w||_1`, leads to sparse weights
+    L1,
+    /// `1/2 ||w||_2^2`
+    #[default]
+    L2,
+}
+
+/// Weights of the classes, the box constraint of a sample is `c` times the weight of
+/// its class.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ClassWeight {
+    /// every class has weight 1
+    #[default]
+    Uniform,
+    /// weights inversely proportional to the class frequencies,
+    /// `n_samples / (n_classes * n_samples_of_class)`
+    Balanced,
+    /// pairs of label and weight, classes not listed have weight 1
+    Manual(Vec<(i32, f64)>),
+}
+
+impl ClassWeight {
+    /// Weight of the class of every label in `y`.
+    pub fn sample_weights(&self, y: &[i32]) -> Vec<f64> {
+        match self {
+            ClassWeight::Uniform => vec![1.0; y.len()],
+            ClassWeight::Balanced => {
+                let mut counts = HashMap::new();
+                for label in y {
+                    *counts.entry(label).or_insert(0) += 1;
+                }
+                y.iter()
+                    .map(|label| y.len() as f64 / (counts.len() * counts[label]) as f64)
+                    .collect()
+            }
+            ClassWeight::Manual(weights) => y
+                .iter()
+                .map(|label| {
+                    weights
+                        .iter()
+                        .find(|(class, _)| class == label)
+                        .map_or(1.0, |&(_, weight)| weight)
+                })
+                .collect(),
+        }
+    }
+}
+
+fn default_epsilon() -> f64 {
+    0.1
+}
+
+fn default_nu() -> f64 {
+    0.5
+}
+
+fn default_cache_size() -> usize {
+    DEFAULT_CACHE_SIZE
 }
 
-fn serialize_kernel<S>(kernel: &Box<dyn Kernel>, serializer: S) -> Result<S::Ok, S::Error>
+#[allow(clippy::borrowed_box)]
+fn serialize_kernel<T: Float, S>(
+    kernel: &Box<dyn Kernel<T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
@@ -29,43 +155,99 @@ where
     kernel_type.serialize(serializer)
 }
 
-fn deserialize_kernel<'de, D>(deserializer: D) -> Result<Box<dyn Kernel>, D::Error>
+fn deserialize_kernel<'de, T: Float, D>(deserializer: D) -> Result<Box<dyn Kernel<T>>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let kernel_type = KernelType::deserialize(deserializer)?;
-    Ok(kernel_type.new())
+    kernel_type.new::<T>().map_err(serde::de::Error::custom)
 }
 
-impl Parameters {
-    pub fn new(kernel: Box<dyn Kernel>, c: f64, tol: f64, epochs: usize) -> Self {
+impl<T: Float> Parameters<T> {
+    pub fn new(kernel: Box<dyn Kernel<T>>, c: f64, tol: f64, epochs: usize) -> Self {
         Parameters {
             kernel,
+            precomputed: false,
             c,
             tol,
             epochs,
+            epsilon: default_epsilon(),
+            nu: default_nu(),
+            formulation: Formulation::default(),
+            probability: false,
+            class_weight: ClassWeight::default(),
+            loss: Loss::default(),
+            penalty: Penalty::default(),
+            max_iter: None,
+            max_time: None,
+            cache_size: default_cache_size(),
         }
     }
 }
 
+impl<T: Float> Parameters<T> {
+    /// Clones the parameters, fails if the kernel can not be rebuilt from its type.
+    ///
+    /// Kernels are cloned by rebuilding them from their [`KernelType`], so custom kernels
+    /// have to be registered in [`kernel::registry`].
+    pub fn try_clone(&self) -> Result<Self, KernelError> {
+        let kernel = self.kernel.type_of().new::<T>()?;
+        Ok(Parameters {
+            kernel,
+            precomputed: self.precomputed,
+            c: self.c,
+            tol: self.tol,
+            epochs: self.epochs,
+            epsilon: self.epsilon,
+            nu: self.nu,
+            formulation: self.formulation,
+            probability: self.probability,
+            class_weight: self.class_weight.clone(),
+            loss: self.loss,
+            penalty: self.penalty,
+            max_iter: self.max_iter,
+            max_time: self.max_time,
+            cache_size: self.cache_size,
+        })
+    }
+}
+
 impl Default for Parameters {
     fn default() -> Self {
         let kernel = kernel::RBFKernel::new(1.0);
         Parameters {
             kernel: Box::new(kernel),
+            precomputed: false,
             c: 1.0,
             tol: 1e-3,
             epochs: 2,
+            epsilon: default_epsilon(),
+            nu: default_nu(),
+            formulation: Formulation::default(),
+            probability: false,
+            class_weight: ClassWeight::default(),
+            loss: Loss::default(),
+            penalty: Penalty::default(),
+            max_iter: None,
+            max_time: None,
+            cache_size: default_cache_size(),
         }
     }
 }
 
-impl Parameters {
-    pub fn with_kernel(&mut self, kernel: Box<dyn Kernel>) -> &mut Self {
+impl<T: Float> Parameters<T> {
+    pub fn with_kernel(&mut self, kernel: Box<dyn Kernel<T>>) -> &mut Self {
         self.kernel = kernel;
         self
     }
 
+    /// Trains on the n×n kernel matrix between the training samples and predicts from the
+    /// m×n kernel matrix between the test and the training samples.
+    pub fn with_precomputed(&mut self, precomputed: bool) -> &mut Self {
+        self.precomputed = precomputed;
+        self
+    }
+
     pub fn with_c(&mut self, c: f64) -> &mut Self {
         self.c = c;
         self
@@ -80,6 +262,56 @@ impl Parameters {
         self.epochs = max_passes;
         self
     }
+
+    pub fn with_epsilon(&mut self, epsilon: f64) -> &mut Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn with_nu(&mut self, nu: f64) -> &mut Self {
+        self.nu = nu;
+        self
+    }
+
+    pub fn with_formulation(&mut self, formulation: Formulation) -> &mut Self {
+        self.formulation = formulation;
+        self
+    }
+
+    pub fn with_probability(&mut self, probability: bool) -> &mut Self {
+        self.probability = probability;
+        self
+    }
+
+    pub fn with_class_weight(&mut self, class_weight: ClassWeight) -> &mut Self {
+        self.class_weight = class_weight;
+        self
+    }
+
+    pub fn with_loss(&mut self, loss: Loss) -> &mut Self {
+        self.loss = loss;
+        self
+    }
+
+    pub fn with_penalty(&mut self, penalty: Penalty) -> &mut Self {
+        self.penalty = penalty;
+        self
+    }
+
+    pub fn with_max_iter(&mut self, max_iter: usize) -> &mut Self {
+        self.max_iter = Some(max_iter);
+        self
+    }
+
+    pub fn with_max_time(&mut self, max_time: Duration) -> &mut Self {
+        self.max_time = Some(max_time);
+        self
+    }
+
+    pub fn with_cache_size(&mut self, cache_size: usize) -> &mut Self {
+        self.cache_size = cache_size;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -92,10 +324,71 @@ mod tests {
         assert_eq!(params.c, 1.0);
         assert_eq!(params.tol, 1e-3);
         assert_eq!(params.epochs, 2);
+        assert_eq!(params.epsilon, 0.1);
+        assert_eq!(params.nu, 0.5);
 
-        params.with_c(2.0).with_tol(2e-3).with_max_passes(10);
+        params
+            .with_c(2.0)
+            .with_tol(2e-3)
+            .with_max_passes(10)
+            .with_epsilon(0.5)
+            .with_nu(0.1);
         assert_eq!(params.c, 2.0);
         assert_eq!(params.tol, 2e-3);
         assert_eq!(params.epochs, 10);
+        assert_eq!(params.epsilon, 0.5);
+        assert_eq!(params.nu, 0.1);
+    }
+
+    #[test]
+    fn test_serialize_polynomial_kernel() {
+        let mut params = Parameters::default();
+        params.with_kernel(Box::new(kernel::PolynomialKernel::new(2, 0.5, 1.0)));
+
+        let json = serde_json::to_string(&params).unwrap();
+        let params: Parameters = serde_json::from_str(&json).unwrap();
+
+        assert!(matches!(
+            params.kernel.type_of(),
+            KernelType::Polynomial {
+                degree,
+                gamma,
+                coef0,
+            } if degree == 2 && gamma == 0.5 && coef0 == 1.0
+        ));
+        assert_eq!(params.kernel.compute(&[1.0, 2.0], &[3.0, 4.0]), 42.25);
+    }
+
+    #[test]
+    fn test_serialize_sigmoid_kernel() {
+        let mut params = Parameters::default();
+        params.with_kernel(Box::new(kernel::SigmoidKernel::new(0.1, -1.0)));
+
+        let json = serde_json::to_string(&params).unwrap();
+        let params: Parameters = serde_json::from_str(&json).unwrap();
+
+        assert!(matches!(
+            params.kernel.type_of(),
+            KernelType::Sigmoid { gamma, coef0 } if gamma == 0.1 && coef0 == -1.0
+        ));
+    }
+
+    #[test]
+    fn test_class_weight() {
+        let y = vec![0, 0, 0, 1];
+
+        assert_eq!(ClassWeight::Uniform.sample_weights(&y), vec![1.0; 4]);
+        assert_eq!(
+            ClassWeight::Balanced.sample_weights(&y),
+            vec![4.0 / 6.0, 4.0 / 6.0, 4.0 / 6.0, 2.0]
+        );
+        assert_eq!(
+            ClassWeight::Balanced.sample_weights(&[2, 7, 2, 5, 7, 2]),
+            vec![2.0 / 3.0, 1.0, 2.0 / 3.0, 2.0, 1.0, 2.0 / 3.0]
+        );
+        assert_eq!(
+            ClassWeight::Manual(vec![(1, 5.0)]).sample_weights(&y),
+            vec![1.0, 1.0, 1.0, 5.0]
+        );
     }
 }
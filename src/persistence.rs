@@ -0,0 +1,468 @@
+//! Versioned envelope of saved models.
+//!
+//! A model file is a JSON object with a header and the serialized model:
+//!
+//! ```json
+//! {"format":"svm-burns","crate_version":"0.1.0","schema_version":1,"kernel":"Linear",
+//!  "n_features":4,"checksum":"…","model":{…}}
+//! ```
+//!
+//! The checksum is the 64 bit FNV-1a hash of the model text, so a truncated or edited
+//! model is rejected instead of silently mispredicting.
+//!
+//! The binary format starts with [`BINARY_MAGIC`], the schema version, the crate version
+//! and the size of the floats, followed by the fields of the model in little-endian
+//! byte order. Vectors are prefixed by their length and their values are packed, so
+//! loading large models needs no text parsing. The file ends with the 64 bit FNV-1a hash
+//! of all preceding bytes.
+
+use std::{
+    io::{ErrorKind, Read, Write},
+    mem,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+use crate::{
+    error::{Error, Result},
+    float::Float,
+    kernel::KernelType,
+};
+
+/// Identifies model files of this crate.
+pub const FORMAT: &str = "svm-burns";
+
+/// Version of the layout of the envelope and the models, increased on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// First bytes of binary model files.
+pub const BINARY_MAGIC: [u8; 4] = *b"SVMB";
+
+/// Header of a model file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    pub format: String,
+    /// version of the crate that wrote the file
+    pub crate_version: String,
+    pub schema_version: u32,
+    pub kernel: KernelType,
+    pub n_features: Option<usize>,
+}
+
+impl Header {
+    /// Header of a model written by this version of the crate.
+    pub fn new(kernel: KernelType, n_features: Option<usize>) -> Self {
+        Header {
+            format: FORMAT.to_string(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: SCHEMA_VERSION,
+            kernel,
+            n_features,
+        }
+    }
+
+    /// Checks that a model with this header can be read by this version of the crate.
+    pub fn check(&self) -> Result<()> {
+        if self.format != FORMAT {
+            return Err(Error::Format(format!(
+                "expected format \"{FORMAT}\", got \"{}\"",
+                self.format
+            )));
+        }
+        if self.schema_version != SCHEMA_VERSION {
+            return Err(Error::Format(format!(
+                "schema version {} written by svm-burns {} is not supported, expected {SCHEMA_VERSION}",
+                self.schema_version, self.crate_version
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    #[serde(flatten)]
+    header: Header,
+    checksum: String,
+    model: Box<RawValue>,
+}
+
+/// Writes `model` with its header.
+pub(crate) fn write<M: Serialize>(writer: impl Write, header: Header, model: &M) -> Result<()> {
+    let model = serde_json::value::to_raw_value(model)
+        .map_err(|err| Error::Format(format!("can not serialize the model: {err}")))?;
+    let envelope = Envelope {
+        header,
+        checksum: checksum(model.get()),
+        model,
+    };
+    serde_json::to_writer(writer, &envelope).map_err(|err| {
+        if err.is_io() {
+            Error::Io(err.into())
+        } else {
+            Error::Format(err.to_string())
+        }
+    })
+}
+
+/// Reads a model and its header, the header is checked against this version of the crate
+/// and the model against the checksum.
+pub(crate) fn read<M: DeserializeOwned>(reader: impl Read) -> Result<(Header, M)> {
+    let envelope: Envelope = serde_json::from_reader(reader).map_err(|err| {
+        if err.is_io() {
+            Error::Io(err.into())
+        } else {
+            Error::Format(format!("not a model file: {err}"))
+        }
+    })?;
+    envelope.header.check()?;
+    if checksum(envelope.model.get()) != envelope.checksum {
+        return Err(Error::Format(
+            "checksum mismatch, the model is corrupted".to_string(),
+        ));
+    }
+    let model = serde_json::from_str(envelope.model.get())
+        .map_err(|err| Error::Format(format!("can not read the model: {err}")))?;
+    Ok((envelope.header, model))
+}
+
+/// Initial state of the FNV-1a hash.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Continues the 64 bit FNV-1a hash `hash` with `bytes`.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// 64 bit FNV-1a hash as hex string.
+fn checksum(text: &str) -> String {
+    format!("{:016x}", fnv1a(FNV_OFFSET, text.as_bytes()))
+}
+
+/// Little-endian encoder of binary model files.
+pub(crate) struct BinaryWriter<W> {
+    writer: W,
+    float_size: usize,
+    /// hash of the bytes written so far
+    hash: u64,
+}
+
+impl<W: Write> BinaryWriter<W> {
+    /// Writes the header of a model with floats of type `T`.
+    pub fn new<T: Float>(writer: W) -> Result<Self> {
+        let mut binary = BinaryWriter {
+            writer,
+            float_size: mem::size_of::<T>(),
+            hash: FNV_OFFSET,
+        };
+        binary.write_all(&BINARY_MAGIC)?;
+        binary.u32(SCHEMA_VERSION)?;
+        binary.bytes(env!("CARGO_PKG_VERSION").as_bytes())?;
+        binary.u8(binary.float_size as u8)?;
+        Ok(binary)
+    }
+
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        self.hash = fnv1a(self.hash, bytes);
+        Ok(self.writer.write_all(bytes)?)
+    }
+
+    /// Writes the checksum trailer, nothing may be written after it.
+    pub fn finish(mut self) -> Result<()> {
+        Ok(self.writer.write_all(&self.hash.to_le_bytes())?)
+    }
+
+    pub fn u8(&mut self, value: u8) -> Result<()> {
+        self.write_all(&[value])
+    }
+
+    pub fn u32(&mut self, value: u32) -> Result<()> {
+        self.write_all(&value.to_le_bytes())
+    }
+
+    pub fn usize(&mut self, value: usize) -> Result<()> {
+        self.write_all(&(value as u64).to_le_bytes())
+    }
+
+    pub fn f64(&mut self, value: f64) -> Result<()> {
+        self.write_all(&value.to_le_bytes())
+    }
+
+    /// Writes a single float as `f64`, which holds `f32` values exactly.
+    pub fn float<T: Float>(&mut self, value: T) -> Result<()> {
+        self.f64(value.as_f64())
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.usize(bytes.len())?;
+        self.write_all(bytes)
+    }
+
+    /// Writes the length of `values` and the packed values.
+    pub fn floats<T: Float>(&mut self, values: &[T]) -> Result<()> {
+        let mut bytes = Vec::with_capacity(8 + values.len() * self.float_size);
+        bytes.extend_from_slice(&(values.len() as u64).to_le_bytes());
+        for value in values {
+            match self.float_size {
+                4 => bytes.extend_from_slice(&(value.as_f64() as f32).to_le_bytes()),
+                _ => bytes.extend_from_slice(&value.as_f64().to_le_bytes()),
+            }
+        }
+        self.write_all(&bytes)
+    }
+
+    pub fn usizes(&mut self, values: &[usize]) -> Result<()> {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|&value| (value as u64).to_le_bytes())
+            .collect();
+        self.bytes(&bytes)
+    }
+
+    pub fn i32s(&mut self, values: &[i32]) -> Result<()> {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        self.bytes(&bytes)
+    }
+
+    /// Writes whether `value` is present and the value.
+    pub fn option<V>(
+        &mut self,
+        value: Option<V>,
+        write: impl FnOnce(&mut Self, V) -> Result<()>,
+    ) -> Result<()> {
+        match value {
+            Some(value) => {
+                self.u8(1)?;
+                write(self, value)
+            }
+            None => self.u8(0),
+        }
+    }
+}
+
+/// Little-endian decoder of binary model files.
+pub(crate) struct BinaryReader<R> {
+    reader: R,
+    float_size: usize,
+    /// hash of the bytes read so far
+    hash: u64,
+}
+
+impl<R: Read> BinaryReader<R> {
+    /// Reads and checks the header of a model with floats of type `T`.
+    pub fn new<T: Float>(reader: R) -> Result<Self> {
+        let mut binary = BinaryReader {
+            reader,
+            float_size: mem::size_of::<T>(),
+            hash: FNV_OFFSET,
+        };
+        if binary.exact(BINARY_MAGIC.len())? != BINARY_MAGIC {
+            return Err(Error::Format("not a binary model file".to_string()));
+        }
+        let schema_version = binary.u32()?;
+        let crate_version = String::from_utf8_lossy(&binary.bytes()?).into_owned();
+        if schema_version != SCHEMA_VERSION {
+            return Err(Error::Format(format!(
+                "schema version {schema_version} written by svm-burns {crate_version} is not supported, expected {SCHEMA_VERSION}"
+            )));
+        }
+        let float_size = binary.u8()? as usize;
+        if float_size != binary.float_size {
+            return Err(Error::Format(format!(
+                "the model has {} bit floats, expected {}",
+                8 * float_size,
+                8 * binary.float_size
+            )));
+        }
+        Ok(binary)
+    }
+
+    /// Reads `n` bytes, without allocating them upfront for corrupted lengths.
+    fn exact(&mut self, n: usize) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        (&mut self.reader).take(n as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != n {
+            return Err(Error::Format(
+                "unexpected end of the model file".to_string(),
+            ));
+        }
+        self.hash = fnv1a(self.hash, &bytes);
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self.unhashed_array()?;
+        self.hash = fnv1a(self.hash, &bytes);
+        Ok(bytes)
+    }
+
+    fn unhashed_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.reader
+            .read_exact(&mut bytes)
+            .map_err(|err| match err.kind() {
+                ErrorKind::UnexpectedEof => {
+                    Error::Format("unexpected end of the model file".to_string())
+                }
+                _ => Error::Io(err),
+            })?;
+        Ok(bytes)
+    }
+
+    /// Reads the checksum trailer and compares it with the hash of the bytes read.
+    pub fn finish(mut self) -> Result<()> {
+        let checksum = u64::from_le_bytes(self.unhashed_array()?);
+        if checksum != self.hash {
+            return Err(Error::Format(
+                "checksum mismatch, the model is corrupted".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub fn usize(&mut self) -> Result<usize> {
+        let value = u64::from_le_bytes(self.array()?);
+        usize::try_from(value)
+            .map_err(|_| Error::Format(format!("{value} is out of range for this platform")))
+    }
+
+    pub fn f64(&mut self) -> Result<f64> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    pub fn float<T: Float>(&mut self) -> Result<T> {
+        Ok(T::cast(self.f64()?))
+    }
+
+    pub fn bytes(&mut self) -> Result<Vec<u8>> {
+        let n = self.usize()?;
+        self.exact(n)
+    }
+
+    fn elements(&mut self, size: usize) -> Result<Vec<u8>> {
+        let n = self.usize()?;
+        let n_bytes = n
+            .checked_mul(size)
+            .ok_or_else(|| Error::Format(format!("invalid length {n}")))?;
+        self.exact(n_bytes)
+    }
+
+    pub fn floats<T: Float>(&mut self) -> Result<Vec<T>> {
+        let bytes = self.elements(self.float_size)?;
+        Ok(match self.float_size {
+            4 => bytes
+                .chunks_exact(4)
+                .map(|chunk| T::cast(f32::from_le_bytes(chunk.try_into().unwrap()) as f64))
+                .collect(),
+            _ => bytes
+                .chunks_exact(8)
+                .map(|chunk| T::cast(f64::from_le_bytes(chunk.try_into().unwrap())))
+                .collect(),
+        })
+    }
+
+    pub fn usizes(&mut self) -> Result<Vec<usize>> {
+        let bytes = self.bytes()?;
+        if bytes.len() % 8 != 0 {
+            return Err(Error::Format(format!("{} bytes of indices", bytes.len())));
+        }
+        bytes
+            .chunks_exact(8)
+            .map(|chunk| {
+                let value = u64::from_le_bytes(chunk.try_into().unwrap());
+                usize::try_from(value).map_err(|_| {
+                    Error::Format(format!("{value} is out of range for this platform"))
+                })
+            })
+            .collect()
+    }
+
+    pub fn i32s(&mut self) -> Result<Vec<i32>> {
+        let bytes = self.bytes()?;
+        if bytes.len() % 4 != 0 {
+            return Err(Error::Format(format!("{} bytes of labels", bytes.len())));
+        }
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|chunk| i32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect())
+    }
+
+    /// Reads a value written by [`BinaryWriter::option`].
+    pub fn option<V>(&mut self, read: impl FnOnce(&mut Self) -> Result<V>) -> Result<Option<V>> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => read(self).map(Some),
+            flag => Err(Error::Format(format!("invalid option flag {flag}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sparse::CsrMatrix;
+
+    #[test]
+    fn envelope() {
+        let mut buffer = Vec::new();
+        write(
+            &mut buffer,
+            Header::new(KernelType::Linear, Some(2)),
+            &vec![1.5, -0.1],
+        )
+        .unwrap();
+
+        let (header, model): (Header, Vec<f64>) = read(buffer.as_slice()).unwrap();
+        assert_eq!(header, Header::new(KernelType::Linear, Some(2)));
+        assert_eq!(model, vec![1.5, -0.1]);
+
+        let text = String::from_utf8(buffer).unwrap();
+        let corrupted = text.replace("1.5", "2.5");
+        assert!(matches!(
+            read::<Vec<f64>>(corrupted.as_bytes()),
+            Err(Error::Format(message)) if message.contains("checksum")
+        ));
+        let newer = text.replace("\"schema_version\":1", "\"schema_version\":99");
+        assert!(matches!(
+            read::<Vec<f64>>(newer.as_bytes()),
+            Err(Error::Format(message)) if message.contains("schema version 99")
+        ));
+        assert!(matches!(
+            read::<Vec<f64>>(&text.as_bytes()[..20]),
+            Err(Error::Format(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_sparse_matrix() {
+        // the checksum matches, but indptr points past the entries
+        let mut buffer = Vec::new();
+        let model =
+            serde_json::json!({"n_cols": 2, "indptr": [0, 5], "indices": [0], "values": [1.0]});
+        write(
+            &mut buffer,
+            Header::new(KernelType::Linear, Some(2)),
+            &model,
+        )
+        .unwrap();
+        assert!(matches!(
+            read::<CsrMatrix>(buffer.as_slice()),
+            Err(Error::Format(message)) if message.contains("indptr")
+        ));
+    }
+}
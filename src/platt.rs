@@ -0,0 +1,125 @@
+// source: https://www.csie.ntu.edu.tw/~cjlin/papers/plattprob.pdf
+// source: https://github.com/cjlin1/libsvm/blob/master/svm.cpp
+
+/// Fits the parameters `(a, b)` of `P(y = 1 | f) = 1 / (1 + exp(a * f + b))` to decision
+/// values and their labels with Newton's method and backtracking line search.
+pub fn sigmoid_train(decision_values: &[f64], labels: &[i32]) -> (f64, f64) {
+    let prior1 = labels.iter().filter(|&&y| y > 0).count() as f64;
+    let prior0 = labels.len() as f64 - prior1;
+
+    let max_iter = 100;
+    let min_step = 1e-10;
+    let sigma = 1e-12;
+    let eps = 1e-5;
+
+    let hi_target = (prior1 + 1.0) / (prior1 + 2.0);
+    let lo_target = 1.0 / (prior0 + 2.0);
+    let targets: Vec<f64> = labels
+        .iter()
+        .map(|&y| if y > 0 { hi_target } else { lo_target })
+        .collect();
+
+    let objective = |a: f64, b: f64| -> f64 {
+        decision_values
+            .iter()
+            .zip(targets.iter())
+            .map(|(&f, &t)| {
+                let f_apb = f * a + b;
+                if f_apb >= 0.0 {
+                    t * f_apb + (-f_apb).exp().ln_1p()
+                } else {
+                    (t - 1.0) * f_apb + f_apb.exp().ln_1p()
+                }
+            })
+            .sum()
+    };
+
+    let mut a = 0.0;
+    let mut b = ((prior0 + 1.0) / (prior1 + 1.0)).ln();
+    let mut fval = objective(a, b);
+
+    for _ in 0..max_iter {
+        // gradient and hessian
+        let (mut h11, mut h22, mut h21, mut g1, mut g2) = (sigma, sigma, 0.0, 0.0, 0.0);
+        for (&f, &t) in decision_values.iter().zip(targets.iter()) {
+            let f_apb = f * a + b;
+            let (p, q) = if f_apb >= 0.0 {
+                let e = (-f_apb).exp();
+                (e / (1.0 + e), 1.0 / (1.0 + e))
+            } else {
+                let e = f_apb.exp();
+                (1.0 / (1.0 + e), e / (1.0 + e))
+            };
+            let d2 = p * q;
+            h11 += f * f * d2;
+            h22 += d2;
+            h21 += f * d2;
+            let d1 = t - p;
+            g1 += f * d1;
+            g2 += d1;
+        }
+
+        if g1.abs() < eps && g2.abs() < eps {
+            break;
+        }
+
+        let det = h11 * h22 - h21 * h21;
+        let d_a = -(h22 * g1 - h21 * g2) / det;
+        let d_b = -(-h21 * g1 + h11 * g2) / det;
+        let gd = g1 * d_a + g2 * d_b;
+
+        let mut step = 1.0;
+        while step >= min_step {
+            let new_a = a + step * d_a;
+            let new_b = b + step * d_b;
+            let new_f = objective(new_a, new_b);
+            if new_f < fval + 0.0001 * step * gd {
+                a = new_a;
+                b = new_b;
+                fval = new_f;
+                break;
+            }
+            step /= 2.0;
+        }
+
+        if step < min_step {
+            // line search failed
+            break;
+        }
+    }
+
+    (a, b)
+}
+
+/// Probability of the positive class for a decision value.
+pub fn sigmoid_predict(decision_value: f64, a: f64, b: f64) -> f64 {
+    let f_apb = decision_value * a + b;
+    if f_apb >= 0.0 {
+        (-f_apb).exp() / (1.0 + (-f_apb).exp())
+    } else {
+        1.0 / (1.0 + f_apb.exp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_sigmoid() {
+        let decision_values = vec![-2.0, -1.5, -1.0, -0.2, 0.3, -0.1, 0.8, 1.2, 1.7, 2.5];
+        let labels = vec![-1, -1, -1, -1, -1, 1, 1, 1, 1, 1];
+
+        let (a, b) = sigmoid_train(&decision_values, &labels);
+
+        assert!(a < 0.0);
+        let probabilities: Vec<f64> = [-2.0, -1.0, 0.0, 1.0, 2.0]
+            .iter()
+            .map(|&f| sigmoid_predict(f, a, b))
+            .collect();
+        assert!(probabilities.windows(2).all(|p| p[0] < p[1]));
+        assert!(probabilities[0] < 0.2);
+        assert!(probabilities[4] > 0.8);
+        assert!((sigmoid_predict(-b / a, a, b) - 0.5).abs() < 1e-12);
+    }
+}
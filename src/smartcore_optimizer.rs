@@ -1,64 +1,97 @@
 // source: https://chubakbidpaa.com/svm/2020/12/27/smo-algorithm-simplifed-copy.html
 // source: https://github.com/smartcorelib/smartcore/blob/development/src/svm/svc.rs
 
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
 
 use rand::{seq::SliceRandom, SeedableRng};
-#[cfg(feature = "parallel")]
-use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use crate::{cache::Cache, optimizer::Optimizer, support_vector::SupportVector, Kernel};
+use crate::{
+    cache::{Cache, CacheStats, DEFAULT_CACHE_SIZE},
+    float::Float,
+    kernel::KernelSource,
+    optimizer::{Convergence, ConvergenceStatus, Optimizer, Progress, ProgressHook, Signal},
+    support_vector::SupportVector,
+};
 
 #[derive(Debug)]
-pub struct SMO {
+pub struct SMO<T: Float = f64> {
     /// regularization parameter
-    c: f64,
+    c: T,
     /// numerical tolerance
-    tol: f64,
+    tol: T,
     /// maximum number of iterations over Larange multipliers without changing
     epochs: usize,
 
     seed: Option<usize>,
+    /// maximum number of pair updates, `max(10^7, 100 n)` if not set
+    max_iter: Option<usize>,
+    /// maximum training time
+    max_time: Option<Duration>,
+    progress: Option<ProgressHook>,
+    /// memory budget of the kernel cache in bytes
+    cache_size: usize,
 
-    cache: Cache,
+    cache: Cache<T>,
 
-    sv: Vec<SupportVector>,
+    sv: Vec<SupportVector<T>>,
 
-    min_grad: f64,
-    max_grad: f64,
+    min_grad: T,
+    max_grad: T,
     min_sv: usize,
     max_sv: usize,
     recalculate_min_max: bool,
+
+    epoch: usize,
+    iterations: usize,
+    iteration_limit: usize,
+    start: Option<Instant>,
+    deadline: Option<Instant>,
+    cancelled: bool,
+    convergence: Option<Convergence>,
 }
 
-unsafe impl Sync for SMO {}
-unsafe impl Send for SMO {}
+unsafe impl<T: Float> Sync for SMO<T> {}
+unsafe impl<T: Float> Send for SMO<T> {}
 
-impl SMO {
-    pub fn new(c: f64, tol: f64, epochs: usize) -> Self {
+impl<T: Float> SMO<T> {
+    pub fn new(c: T, tol: T, epochs: usize) -> Self {
         SMO {
             c,
             tol,
             epochs,
             cache: Cache::new(),
             seed: None,
+            max_iter: None,
+            max_time: None,
+            progress: None,
+            cache_size: DEFAULT_CACHE_SIZE,
             sv: Vec::new(),
-            min_grad: f64::INFINITY,
-            max_grad: f64::NEG_INFINITY,
+            min_grad: T::infinity(),
+            max_grad: T::neg_infinity(),
             min_sv: 0,
             max_sv: 0,
             recalculate_min_max: true,
+            epoch: 0,
+            iterations: 0,
+            iteration_limit: usize::MAX,
+            start: None,
+            deadline: None,
+            cancelled: false,
+            convergence: None,
         }
     }
 }
 
-impl SMO {
-    pub fn with_c(&mut self, c: f64) -> &mut Self {
+impl<T: Float> SMO<T> {
+    pub fn with_c(&mut self, c: T) -> &mut Self {
         self.c = c;
         self
     }
 
-    pub fn with_tol(&mut self, tol: f64) -> &mut Self {
+    pub fn with_tol(&mut self, tol: T) -> &mut Self {
         self.tol = tol;
         self
     }
@@ -72,30 +105,81 @@ impl SMO {
         self.seed = seed;
         self
     }
+
+    pub fn with_max_iter(&mut self, max_iter: Option<usize>) -> &mut Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_max_time(&mut self, max_time: Option<Duration>) -> &mut Self {
+        self.max_time = max_time;
+        self
+    }
+
+    pub fn with_cache_size(&mut self, cache_size: usize) -> &mut Self {
+        self.cache_size = cache_size;
+        self
+    }
+
+    /// Lookups of the kernel cache during the last optimization.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Reports the progress to `progress` every few iterations, the hook can cancel the
+    /// optimization.
+    pub fn with_progress(&mut self, progress: Option<ProgressHook>) -> &mut Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Removes the progress hook, e.g. to reuse it after the optimization.
+    pub fn take_progress(&mut self) -> Option<ProgressHook> {
+        self.progress.take()
+    }
+
+    /// How the last optimization stopped.
+    pub fn convergence(&self) -> Option<Convergence> {
+        self.convergence
+    }
 }
 
-impl Default for SMO {
+impl<T: Float> Default for SMO<T> {
     fn default() -> Self {
-        SMO::new(1.0, 1e-3, 5)
+        SMO::new(T::one(), T::cast(1e-3), 5)
     }
 }
 
+/// permute the indices of the support vectors
+pub fn permutate(n: usize, seed: Option<usize>) -> Vec<usize> {
+    let mut rng = match seed {
+        Some(seed) => rand::rngs::StdRng::seed_from_u64(seed as u64),
+        None => rand::rngs::StdRng::from_entropy(),
+    };
+    let mut perm: Vec<usize> = (0..n).collect();
+    perm.shuffle(&mut rng);
+    perm
+}
+
 impl SMO {
-    pub fn initialize(&mut self, x: &Vec<Vec<f64>>, y: &Vec<i32>, kernel: &Box<dyn Kernel>) {
-        let n = x.len();
+    /// permute the indices of the support vectors
+    pub fn permutate(n: usize, seed: Option<usize>) -> Vec<usize> {
+        permutate(n, seed)
+    }
+}
+
+impl<T: Float> SMO<T> {
+    pub fn initialize(&mut self, source: &dyn KernelSource<T>, targets: &[T], bounds: &[(T, T)]) {
+        let n = source.n_samples();
         let few = 5;
         let mut cp = 0; // count of positive samples
         let mut cn = 0; // count of positive and negative samples
 
-        let mut x_new: Vec<f64> = Vec::with_capacity(n);
-
-        for i in Self::permutate(n, self.seed) {
-            x_new.clear();
-            x_new.extend(x[i].iter().take(n).copied());
-
-            if y[i] == 1 && cp < few && self.process(i, &x_new, y[i], kernel) {
+        for i in permutate(n, self.seed) {
+            let positive = bounds[i].1 > T::zero();
+            if positive && cp < few && self.process(i, targets[i], bounds[i], source) {
                 cp += 1;
-            } else if y[i] == -1 && cn < few && self.process(i, &x_new, y[i], kernel) {
+            } else if !positive && cn < few && self.process(i, targets[i], bounds[i], source) {
                 cn += 1;
             }
 
@@ -105,28 +189,39 @@ impl SMO {
         }
     }
 
-    fn process(&mut self, i: usize, x: &Vec<f64>, y: i32, kernel: &Box<dyn Kernel>) -> bool {
+    fn process(
+        &mut self,
+        i: usize,
+        target: T,
+        (cmin, cmax): (T, T),
+        source: &dyn KernelSource<T>,
+    ) -> bool {
         for j in 0..self.sv.len() {
             if self.sv[j].index == i {
                 return true;
             }
         }
 
-        let mut g: f64 = y.into();
-        let mut cache_values: Vec<((usize, usize), f64)> = Vec::new();
+        let positive = cmax > T::zero();
+        let mut g = target;
+        let mut cache_values: Vec<((usize, usize), T)> = Vec::new();
 
         for v in self.sv.iter() {
-            let xi = &v.x;
-            let xj = x;
-            let k = kernel.compute(xi, xj);
-            cache_values.push(((i, v.index), k));
+            let k = match self.cache.get(i, v.index) {
+                Some(&k) => k,
+                None => {
+                    let k = source.compute(v.index, i);
+                    cache_values.push(((i, v.index), k));
+                    k
+                }
+            };
             g -= v.alpha * k;
         }
 
         self.find_min_max_gradient();
 
         if self.min_grad < self.max_grad
-            && ((y > 0 && g < self.min_grad) || (y < 0 && g > self.max_grad))
+            && ((positive && g < self.min_grad) || (!positive && g > self.max_grad))
         {
             return false;
         }
@@ -135,42 +230,143 @@ impl SMO {
             self.cache.insert(v.0, v.1);
         }
 
-        let k_v = kernel.compute(x, x);
+        let k_v = source.compute(i, i);
 
-        self.sv.insert(
-            0,
-            SupportVector::new(i, x.to_vec(), y.into(), g, self.c, k_v),
-        );
+        self.sv
+            .insert(0, SupportVector::with_bounds(i, g, cmin, cmax, k_v));
+        // the new gradient can be an extreme and the indices of the old ones shift
+        self.recalculate_min_max = true;
 
-        if y > 0 {
-            self.smo(None, Some(0), 0.0, kernel);
+        if positive {
+            self.smo(None, Some(0), T::zero(), source);
         } else {
-            self.smo(Some(0), None, 0.0, kernel);
+            self.smo(Some(0), None, T::zero(), source);
         }
         true
     }
 
-    fn reprocess(&mut self, kernel: &Box<dyn Kernel>) -> bool {
-        let status = self.smo(None, None, self.tol, kernel);
+    /// Adds the variables with a non zero starting alpha as support vectors.
+    fn seed_alphas(
+        &mut self,
+        source: &dyn KernelSource<T>,
+        targets: &[T],
+        bounds: &[(T, T)],
+        alphas: &[T],
+    ) {
+        let seeds: Vec<usize> = (0..alphas.len())
+            .filter(|&i| alphas[i] != T::zero())
+            .collect();
+        for &i in seeds.iter() {
+            let (cmin, cmax) = bounds[i];
+            let mut g = targets[i];
+            for &j in seeds.iter() {
+                let k = self.cache.get_or_compute(i, j, || source.compute(i, j));
+                g -= alphas[j] * k;
+            }
+            let mut sv = SupportVector::with_bounds(i, g, cmin, cmax, source.compute(i, i));
+            sv.alpha = alphas[i];
+            self.sv.push(sv);
+        }
+        self.recalculate_min_max = true;
+    }
+
+    fn reprocess(&mut self, source: &dyn KernelSource<T>) -> bool {
+        let status = self.smo(None, None, self.tol, source);
         self.clean();
         status
     }
 
-    fn finish(&mut self, kernel: &Box<dyn Kernel>) {
+    fn finish(&mut self, source: &dyn KernelSource<T>) {
         let mut max_iter = self.sv.len();
 
-        while self.smo(None, None, self.tol, kernel) && max_iter > 0 {
+        while self.smo(None, None, self.tol, source) && max_iter > 0 {
             max_iter -= 1;
         }
 
         self.clean();
     }
 
+    /// Reason to stop if the training was cancelled or the iteration or time budget is
+    /// used up.
+    fn budget_status(&self) -> Option<ConvergenceStatus> {
+        if self.cancelled {
+            Some(ConvergenceStatus::Cancelled)
+        } else if self.iterations >= self.iteration_limit {
+            Some(ConvergenceStatus::IterationLimit)
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(ConvergenceStatus::TimeLimit)
+        } else {
+            None
+        }
+    }
+
+    /// Training indices and alphas of the support vectors, sorted by index.
+    fn support_alphas(&self) -> Vec<(usize, T)> {
+        let mut alphas: Vec<(usize, T)> = self.sv.iter().map(|v| (v.index, v.alpha)).collect();
+        alphas.sort_unstable_by_key(|&(index, _)| index);
+        alphas
+    }
+
+    /// KKT violation over all variables and the variables outside of the support vectors
+    /// that violate the conditions by more than `tol`.
+    ///
+    /// A variable at zero can increase if `cmax > 0` and decrease if `cmin < 0`, so its
+    /// gradient takes part in the maximum or the minimum gradient. The violators are
+    /// found against the extremes over all variables, two variables outside of the
+    /// support vectors can form the only violating pair.
+    fn violators(
+        &mut self,
+        source: &dyn KernelSource<T>,
+        targets: &[T],
+        bounds: &[(T, T)],
+    ) -> (T, Vec<usize>) {
+        self.find_min_max_gradient();
+        let mut in_sv = vec![false; targets.len()];
+        for v in self.sv.iter() {
+            in_sv[v.index] = true;
+        }
+
+        let (mut max_grad, mut min_grad) = (self.max_grad, self.min_grad);
+        let cache = &mut self.cache;
+        let gradients: Vec<(usize, T)> = (0..targets.len())
+            .filter(|&i| !in_sv[i])
+            .map(|i| {
+                let g = self.sv.iter().fold(targets[i], |g, v| {
+                    g - v.alpha * cache.get_or_compute(v.index, i, || source.compute(v.index, i))
+                });
+                let (cmin, cmax) = bounds[i];
+                if cmax > T::zero() {
+                    max_grad = max_grad.max(g);
+                }
+                if cmin < T::zero() {
+                    min_grad = min_grad.min(g);
+                }
+                (i, g)
+            })
+            .collect();
+        let violators = gradients
+            .into_iter()
+            .filter(|&(i, g)| {
+                let (cmin, cmax) = bounds[i];
+                (cmax > T::zero() && g - min_grad > self.tol)
+                    || (cmin < T::zero() && max_grad - g > self.tol)
+            })
+            .map(|(i, _)| i)
+            .collect();
+        ((max_grad - min_grad).max(T::zero()), violators)
+    }
+
     fn find_min_max_gradient(&mut self) {
         if !self.recalculate_min_max {
             return;
         }
 
+        self.min_grad = T::infinity();
+        self.max_grad = T::neg_infinity();
+
         for i in 0..self.sv.len() {
             let v = &self.sv[i];
             let grad = v.grad;
@@ -201,8 +397,9 @@ impl SMO {
             let cmax = v.cmax;
             let grad = v.grad;
             let idx = v.index;
-            if alpha == 0.0
-                && ((grad >= max_grad && 0.0 >= cmax) || (grad <= min_grad && 0.0 <= cmin))
+            let zero = T::zero();
+            if alpha == zero
+                && ((grad >= max_grad && zero >= cmax) || (grad <= min_grad && zero <= cmin))
             {
                 idxs_to_drop.insert(idx);
                 return false;
@@ -213,114 +410,103 @@ impl SMO {
         self.recalculate_min_max = true;
     }
 
-    /// permute the indices of the support vectors
-    pub fn permutate(n: usize, seed: Option<usize>) -> Vec<usize> {
-        let mut rng = match seed {
-            Some(seed) => rand::rngs::StdRng::seed_from_u64(seed as u64),
-            None => rand::rngs::StdRng::from_entropy(),
-        };
-        let mut perm: Vec<usize> = (0..n).collect();
-        perm.shuffle(&mut rng);
-        perm
-    }
-
     fn select_pair(
         &mut self,
         idx_1: Option<usize>,
         idx_2: Option<usize>,
-        kernel: &Box<dyn Kernel>,
-    ) -> Option<(usize, usize, f64)> {
+        source: &dyn KernelSource<T>,
+    ) -> Option<(usize, usize, T)> {
         match (idx_1, idx_2) {
             (None, None) => {
                 if self.max_grad > -self.min_grad {
-                    self.select_pair(None, Some(self.max_sv), kernel)
+                    self.select_pair(None, Some(self.max_sv), source)
                 } else {
-                    self.select_pair(Some(self.min_sv), None, kernel)
+                    self.select_pair(Some(self.min_sv), None, source)
                 }
             }
             (Some(idx_1), None) => {
                 let sv1 = &self.sv[idx_1];
                 let mut idx_2 = None;
-                let mut k_v_12: Option<f64> = None;
+                let mut k_v_12: Option<T> = None;
                 let km = sv1.k;
                 let gm = sv1.grad;
-                let mut best = 0f64;
-                let xi = &sv1.x;
+                let mut best = T::zero();
                 for i in 0..self.sv.len() {
                     let v = &self.sv[i];
-                    let xj = &v.x;
                     let z = v.grad - gm;
                     let k = self
                         .cache
-                        .get_or_insert((sv1.index, v.index), kernel.compute(&xi, &xj));
-                    let mut curv = km + v.k - 2.0 * k;
-                    if curv <= 0.0 {
-                        curv = 1e-12; // tau
+                        .get_or_compute(sv1.index, v.index, || source.compute(sv1.index, v.index));
+                    let mut curv = km + v.k - T::cast(2.0) * k;
+                    if curv <= T::zero() {
+                        curv = T::cast(1e-12); // tau
                     }
                     let mu = z / curv;
-                    if (mu > 0.0 && v.alpha < v.cmax) || (mu < 0.0 && v.alpha > v.cmin) {
+                    if (mu > T::zero() && v.alpha < v.cmax) || (mu < T::zero() && v.alpha > v.cmin)
+                    {
                         let gain = z * mu;
                         if gain > best {
                             best = gain;
                             idx_2 = Some(i);
-                            k_v_12 = Some(*k);
+                            k_v_12 = Some(k);
                         }
                     }
                 }
 
-                let xi = &self.sv[idx_1].x;
                 idx_2.map(|idx_2| {
                     (
                         idx_1,
                         idx_2,
-                        k_v_12.unwrap_or_else(|| kernel.compute(&xi, &self.sv[idx_2].x)),
+                        k_v_12.unwrap_or_else(|| {
+                            source.compute(self.sv[idx_1].index, self.sv[idx_2].index)
+                        }),
                     )
                 })
             }
             (None, Some(idx_2)) => {
                 let mut idx_1 = None;
                 let sv2 = &self.sv[idx_2];
-                let mut k_v_12: Option<f64> = None;
+                let mut k_v_12: Option<T> = None;
                 let km = sv2.k;
                 let gm = sv2.grad;
-                let mut best = 0f64;
+                let mut best = T::zero();
 
-                let xi = &sv2.x;
                 for i in 0..self.sv.len() {
                     let v = &self.sv[i];
-                    let xj = &v.x;
                     let z = gm - v.grad;
                     let k = self
                         .cache
-                        .get_or_insert((sv2.index, v.index), kernel.compute(&xi, &xj));
-                    let mut curv = km + v.k - 2.0 * k;
-                    if curv <= 0.0 {
-                        curv = 1e-12; // tau
+                        .get_or_compute(sv2.index, v.index, || source.compute(sv2.index, v.index));
+                    let mut curv = km + v.k - T::cast(2.0) * k;
+                    if curv <= T::zero() {
+                        curv = T::cast(1e-12); // tau
                     }
                     let mu = z / curv;
-                    if (mu > 0.0 && v.alpha > v.cmin) || (mu < 0.0 && v.alpha < v.cmax) {
+                    if (mu > T::zero() && v.alpha > v.cmin) || (mu < T::zero() && v.alpha < v.cmax)
+                    {
                         let gain = z * mu;
                         if gain > best {
                             best = gain;
                             idx_1 = Some(i);
-                            k_v_12 = Some(*k);
+                            k_v_12 = Some(k);
                         }
                     }
                 }
 
-                let xj = &self.sv[idx_2].x;
                 idx_1.map(|idx_1| {
                     (
                         idx_1,
                         idx_2,
-                        k_v_12.unwrap_or_else(|| kernel.compute(&self.sv[idx_1].x, &xj)),
+                        k_v_12.unwrap_or_else(|| {
+                            source.compute(self.sv[idx_1].index, self.sv[idx_2].index)
+                        }),
                     )
                 })
             }
             (Some(idx_1), Some(idx_2)) => Some((
                 idx_1,
                 idx_2,
-                kernel.compute(&self.sv[idx_1].x, &self.sv[idx_2].x),
+                source.compute(self.sv[idx_1].index, self.sv[idx_2].index),
             )),
         }
     }
@@ -329,17 +515,20 @@ impl SMO {
         &mut self,
         idx_1: Option<usize>,
         idx_2: Option<usize>,
-        tol: f64,
-        kernel: &Box<dyn Kernel>,
+        tol: T,
+        source: &dyn KernelSource<T>,
     ) -> bool {
-        match self.select_pair(idx_1, idx_2, kernel) {
+        if self.budget_status().is_some() {
+            return false;
+        }
+        match self.select_pair(idx_1, idx_2, source) {
             Some((idx_1, idx_2, k_v_12)) => {
-                let mut curv = self.sv[idx_1].k + self.sv[idx_2].k - 2.0 * k_v_12;
-                if curv >= 0.0 {
-                    curv = 1e-12; // tau
+                let mut curv = self.sv[idx_1].k + self.sv[idx_2].k - T::cast(2.0) * k_v_12;
+                if curv <= T::zero() {
+                    curv = T::cast(1e-12); // tau
                 }
                 let mut step = (self.sv[idx_2].grad - self.sv[idx_1].grad) / curv;
-                if step >= 0.0 {
+                if step >= T::zero() {
                     let mut ostep = self.sv[idx_1].alpha - self.sv[idx_1].cmin;
                     if ostep < step {
                         step = ostep;
@@ -358,19 +547,18 @@ impl SMO {
                         step = ostep;
                     }
                 }
-                self.update(idx_1, idx_2, step, kernel);
+                self.update(idx_1, idx_2, step, source);
                 self.max_grad - self.min_grad > tol
             }
             None => false,
         }
     }
 
-    fn update(&mut self, v1: usize, v2: usize, step: f64, kernel: &Box<dyn Kernel>) {
+    fn update(&mut self, v1: usize, v2: usize, step: T, source: &dyn KernelSource<T>) {
         self.sv[v1].alpha -= step;
         self.sv[v2].alpha += step;
+        self.iterations += 1;
 
-        let xi_v1 = self.sv[v1].x.clone();
-        let xi_v2 = self.sv[v2].x.clone();
         let sv_v1_index = self.sv[v1].index;
         let sv_v2_index = self.sv[v2].index;
 
@@ -379,17 +567,13 @@ impl SMO {
         let mut k2_values = Vec::new();
 
         for i in 0..self.sv.len() {
-            let xj = &self.sv[i].x;
-            k2_values.push(
-                *self
-                    .cache
-                    .get_or_insert((sv_v2_index, self.sv[i].index), kernel.compute(&xi_v2, &xj)),
-            );
-            k1_values.push(
-                *self
-                    .cache
-                    .get_or_insert((sv_v1_index, self.sv[i].index), kernel.compute(&xi_v1, &xj)),
-            );
+            let sv_i_index = self.sv[i].index;
+            k2_values.push(self.cache.get_or_compute(sv_v2_index, sv_i_index, || {
+                source.compute(sv_v2_index, sv_i_index)
+            }));
+            k1_values.push(self.cache.get_or_compute(sv_v1_index, sv_i_index, || {
+                source.compute(sv_v1_index, sv_i_index)
+            }));
         }
 
         for i in 0..self.sv.len() {
@@ -400,50 +584,296 @@ impl SMO {
 
         self.recalculate_min_max = true;
         self.find_min_max_gradient();
+        self.report_progress();
+    }
+
+    fn report_progress(&mut self) {
+        let Some(progress) = self.progress.as_mut() else {
+            return;
+        };
+        let signal = progress.report(self.iterations, || Progress {
+            epoch: self.epoch,
+            iterations: self.iterations,
+            n_support: self.sv.len(),
+            gradient_gap: (self.max_grad - self.min_grad).as_f64(),
+            elapsed: self.start.map(|start| start.elapsed()),
+        });
+        if signal == Signal::Cancel {
+            self.cancelled = true;
+        }
     }
 }
 
-impl Optimizer for SMO {
-    fn optimize(
+impl<T: Float> SMO<T> {
+    /// Solves `max sum_i alpha_i * targets_i - 1/2 sum_ij alpha_i alpha_j K_ij` subject to
+    /// `bounds_i.0 <= alpha_i <= bounds_i.1` and `sum_i alpha_i = 0`.
+    ///
+    /// Returns the indices of the variables with a non zero alpha, their alphas and the bias.
+    pub fn optimize_problem(
         &mut self,
-        x: &Vec<Vec<f64>>,
-        y: &Vec<i32>,
-        kernel: &Box<dyn Kernel>,
-    ) -> (Vec<Vec<f64>>, Vec<f64>, f64) {
-        let n = x.len();
-
-        self.cache = Cache::new();
-
-        self.initialize(x, y, kernel);
-
-        let good_enough = 1000.0;
+        source: &dyn KernelSource<T>,
+        targets: &[T],
+        bounds: &[(T, T)],
+    ) -> (Vec<usize>, Vec<T>, T) {
+        self.optimize_problem_from(source, targets, bounds, &[])
+    }
 
-        let mut x_new = Vec::with_capacity(n);
-        for _ in 0..self.epochs {
-            for i in Self::permutate(n, self.seed) {
-                x_new.clear();
-                x_new.extend(x[i].iter().take(n).copied());
-                self.process(i, &x_new, y[i], kernel);
+    /// Like [`SMO::optimize_problem`] but starts from the feasible point `alphas`, the
+    /// sum of the alphas is kept constant. Missing alphas are zero.
+    ///
+    /// Stops when the KKT violation over all variables is at most `tol`, when the
+    /// iteration or time budget is used up, when a round over the violators changes no
+    /// alpha or when the progress hook cancels, see [`SMO::convergence`]. The alphas stay feasible, so a stopped optimization returns
+    /// the best solution found so far.
+    pub fn optimize_problem_from(
+        &mut self,
+        source: &dyn KernelSource<T>,
+        targets: &[T],
+        bounds: &[(T, T)],
+        alphas: &[T],
+    ) -> (Vec<usize>, Vec<T>, T) {
+        let n = source.n_samples();
+
+        self.cache = Cache::with_capacity(self.cache_size);
+        self.sv.clear();
+        self.min_grad = T::infinity();
+        self.max_grad = T::neg_infinity();
+        self.recalculate_min_max = true;
+        self.epoch = 0;
+        self.iterations = 0;
+        self.iteration_limit = self.max_iter.unwrap_or(10_000_000.max(100 * n));
+        // the clock is only read if needed, it is not available on every target
+        self.start = (self.max_time.is_some() || self.progress.is_some()).then(Instant::now);
+        self.deadline = self
+            .start
+            .zip(self.max_time)
+            .map(|(start, max_time)| start + max_time);
+        self.cancelled = false;
+
+        self.seed_alphas(source, targets, bounds, alphas);
+        self.initialize(source, targets, bounds);
+
+        let good_enough = T::cast(1000.0);
+        let mut status = None;
+
+        'epochs: for epoch in 0..self.epochs {
+            self.epoch = epoch;
+            for i in permutate(n, self.seed) {
+                self.process(i, targets[i], bounds[i], source);
                 loop {
-                    self.reprocess(kernel);
+                    status = self.budget_status();
+                    if status.is_some() {
+                        break 'epochs;
+                    }
+                    let progress = self.reprocess(source);
                     self.find_min_max_gradient();
-                    if self.max_grad - self.min_grad < good_enough {
+                    if !progress || self.max_grad - self.min_grad < good_enough {
                         break;
                     }
                 }
             }
         }
 
-        self.finish(kernel);
-        let mut support_vectors = Vec::new();
+        self.epoch = self.epochs;
+        let mut kkt_violation;
+        let status = match status {
+            Some(status) => {
+                (kkt_violation, _) = self.violators(source, targets, bounds);
+                status
+            }
+            None => loop {
+                let round = self.support_alphas();
+                self.finish(source);
+                let violators;
+                (kkt_violation, violators) = self.violators(source, targets, bounds);
+                if kkt_violation <= self.tol {
+                    break ConvergenceStatus::Converged;
+                }
+                if let Some(status) = self.budget_status() {
+                    break status;
+                }
+                for i in violators {
+                    self.process(i, targets[i], bounds[i], source);
+                }
+                // with the same support vectors and alphas the next round repeats this
+                // one, the tolerance can not be reached
+                if self.support_alphas() == round {
+                    break ConvergenceStatus::IterationLimit;
+                }
+            },
+        };
+        self.find_min_max_gradient();
+        self.convergence = Some(Convergence {
+            status,
+            iterations: self.iterations,
+            kkt_violation: kkt_violation.max(T::zero()).as_f64(),
+        });
+
+        let mut support = Vec::new();
         let mut w = Vec::new();
-        let b = (self.min_grad + self.max_grad) / 2.0;
+        let b = (self.min_grad + self.max_grad) / T::cast(2.0);
 
         for v in self.sv.iter() {
-            support_vectors.push(v.x.clone());
+            support.push(v.index);
             w.push(v.alpha);
         }
 
-        (support_vectors, w, b)
+        (support, w, b)
+    }
+}
+
+impl<T: Float> SMO<T> {
+    /// Solves the C-SVC problem in which the box of every sample is scaled by its weight,
+    /// `0 <= alpha_i <= c * weights_i` for positive and `-c * weights_i <= alpha_i <= 0`
+    /// for negative samples.
+    pub fn optimize_weighted(
+        &mut self,
+        source: &dyn KernelSource<T>,
+        y: &[i32],
+        weights: &[T],
+    ) -> (Vec<usize>, Vec<T>, T) {
+        let targets: Vec<T> = y.iter().map(|&y_i| T::cast(y_i.into())).collect();
+        let bounds: Vec<(T, T)> = y
+            .iter()
+            .zip(weights.iter())
+            .map(|(&y_i, &weight)| {
+                let c = self.c * weight;
+                if y_i > 0 {
+                    (T::zero(), c)
+                } else {
+                    (-c, T::zero())
+                }
+            })
+            .collect();
+        self.optimize_problem(source, &targets, &bounds)
+    }
+}
+
+impl<T: Float> Optimizer<T> for SMO<T> {
+    fn optimize(&mut self, source: &dyn KernelSource<T>, y: &[i32]) -> (Vec<usize>, Vec<T>, T) {
+        self.optimize_weighted(source, y, &vec![T::one(); y.len()])
+    }
+
+    fn convergence(&self) -> Option<Convergence> {
+        self.convergence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_min_max_gradient_rescans() {
+        let sv = |index, grad| SupportVector::with_bounds(index, grad, -1.0, 1.0, 1.0);
+        let mut smo = SMO::new(1.0, 1e-3, 2);
+        smo.sv = vec![sv(0, 2.0), sv(1, -2.0)];
+        smo.find_min_max_gradient();
+        assert_eq!((smo.min_grad, smo.max_grad), (-2.0, 2.0));
+
+        // the gap shrinks after an update, stale extremes would keep the old one
+        smo.sv[0].grad = 0.5;
+        smo.sv[1].grad = -0.5;
+        smo.recalculate_min_max = true;
+        smo.find_min_max_gradient();
+        assert_eq!((smo.min_grad, smo.max_grad), (-0.5, 0.5));
+    }
+
+    struct Linear(Vec<f64>);
+
+    impl KernelSource for Linear {
+        fn n_samples(&self) -> usize {
+            self.0.len()
+        }
+
+        fn compute(&self, i: usize, j: usize) -> f64 {
+            self.0[i] * self.0[j]
+        }
+    }
+
+    #[test]
+    fn clock_only_with_budget() {
+        let source = Linear(vec![1.0, -1.0, 2.0]);
+        let targets = [1.0, -1.0, 1.0];
+        let bounds = [(0.0, 1.0), (-1.0, 0.0), (0.0, 1.0)];
+        let mut smo = SMO::new(1.0, 1e-3, 2);
+        smo.optimize_problem(&source, &targets, &bounds);
+        assert!(smo.start.is_none());
+
+        smo.with_max_time(Some(Duration::from_secs(60)));
+        smo.optimize_problem(&source, &targets, &bounds);
+        assert!(smo.start.is_some() && smo.deadline.is_some());
+    }
+
+    /// Problem on the line `x = (i - 2) / 1.3`, labeled by the sign of `x`.
+    fn line() -> (Linear, Vec<f64>, Vec<(f64, f64)>) {
+        let source = Linear((0..5).map(|i| (i as f64 - 2.0) / 1.3).collect());
+        let targets: Vec<f64> = source
+            .0
+            .iter()
+            .map(|&x| if x >= 0.0 { 1.0 } else { -1.0 })
+            .collect();
+        let bounds = targets
+            .iter()
+            .map(|&y| if y > 0.0 { (0.0, 1.0) } else { (-1.0, 0.0) })
+            .collect();
+        (source, targets, bounds)
+    }
+
+    #[test]
+    fn violating_pair_outside_support_vectors() {
+        // with this seed both support vectors end up at a bound and the only violating
+        // pair is formed by two samples that are not support vectors
+        let (source, targets, bounds) = line();
+        let mut smo = SMO::new(1.0, 1e-3, 2);
+        smo.with_seed(Some(4));
+        smo.optimize_problem(&source, &targets, &bounds);
+        let convergence = smo.convergence.unwrap();
+        assert_eq!(convergence.status, ConvergenceStatus::Converged);
+        assert!(convergence.kkt_violation <= 1e-3);
+    }
+
+    #[test]
+    fn stall_stops() {
+        // the KKT violation is never negative, no round can reach this tolerance
+        let (source, targets, bounds) = line();
+        let mut smo = SMO::new(1.0, -1.0, 2);
+        smo.optimize_problem(&source, &targets, &bounds);
+        let convergence = smo.convergence.unwrap();
+        assert_eq!(convergence.status, ConvergenceStatus::IterationLimit);
+        assert!(convergence.iterations < smo.iteration_limit);
+    }
+
+    #[test]
+    fn kkt_violation_after_budget() {
+        let source = Linear((0..30).map(|i| (i as f64 - 14.5) / 5.0).collect());
+        let targets: Vec<f64> = source.0.iter().map(|x| x.signum()).collect();
+        let bounds: Vec<(f64, f64)> = targets
+            .iter()
+            .map(|&y| if y > 0.0 { (0.0, 1.0) } else { (-1.0, 0.0) })
+            .collect();
+        let mut smo = SMO::new(1.0, 1e-3, 2);
+        smo.with_max_iter(Some(1));
+        let (support, alphas, _) = smo.optimize_problem(&source, &targets, &bounds);
+        let convergence = smo.convergence.unwrap();
+        assert_eq!(convergence.status, ConvergenceStatus::IterationLimit);
+        assert!(support.len() < targets.len());
+
+        // the violation over all variables, not only over the support vectors
+        let mut alpha = vec![0.0; targets.len()];
+        for (&i, &alpha_i) in support.iter().zip(&alphas) {
+            alpha[i] = alpha_i;
+        }
+        let (mut max_grad, mut min_grad) = (f64::NEG_INFINITY, f64::INFINITY);
+        for i in 0..targets.len() {
+            let g = (0..targets.len()).fold(targets[i], |g, j| g - alpha[j] * source.compute(j, i));
+            if alpha[i] < bounds[i].1 {
+                max_grad = max_grad.max(g);
+            }
+            if alpha[i] > bounds[i].0 {
+                min_grad = min_grad.min(g);
+            }
+        }
+        assert!((convergence.kkt_violation - (max_grad - min_grad)).abs() < 1e-9);
     }
 }
@@ -0,0 +1,342 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    float::Float,
+    matrix::Matrix,
+};
+
+/// Borrowed sparse vector, `indices` are strictly increasing.
+#[derive(Debug, Clone, Copy)]
+pub struct SparseRow<'a, T = f64> {
+    pub indices: &'a [usize],
+    pub values: &'a [T],
+}
+
+impl<T: Float> SparseRow<'_, T> {
+    /// Dense copy with `n` features.
+    pub fn to_dense(&self, n: usize) -> Vec<T> {
+        let mut dense = vec![T::zero(); n];
+        for (&index, &value) in self.indices.iter().zip(self.values.iter()) {
+            dense[index] = value;
+        }
+        dense
+    }
+}
+
+/// Owned sparse vector.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: Float", try_from = "SparseParts<T>")]
+pub struct SparseVector<T = f64> {
+    indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+/// Fields of a [`SparseVector`] before they are validated by [`SparseVector::new`].
+#[derive(Deserialize)]
+#[serde(bound = "T: Float")]
+struct SparseParts<T> {
+    indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Float> TryFrom<SparseParts<T>> for SparseVector<T> {
+    type Error = Error;
+
+    fn try_from(parts: SparseParts<T>) -> Result<Self> {
+        Self::new(parts.indices, parts.values)
+    }
+}
+
+impl<T: Float> SparseVector<T> {
+    /// Creates a sparse vector from strictly increasing `indices` and their values.
+    pub fn new(indices: Vec<usize>, values: Vec<T>) -> Result<Self> {
+        check_row(&indices, &values, None)?;
+        Ok(SparseVector { indices, values })
+    }
+
+    /// Keeps the non-zero entries of `x`.
+    pub fn from_dense(x: &[T]) -> Self {
+        let (indices, values) = x
+            .iter()
+            .enumerate()
+            .filter(|(_, &value)| value != T::zero())
+            .map(|(index, &value)| (index, value))
+            .unzip();
+        SparseVector { indices, values }
+    }
+
+    pub fn as_row(&self) -> SparseRow<'_, T> {
+        SparseRow {
+            indices: &self.indices,
+            values: &self.values,
+        }
+    }
+}
+
+/// Sparse matrix in compressed sparse row format, the entries of row `i` are at
+/// `indptr[i]..indptr[i + 1]` in `indices` and `values`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: Float", try_from = "CsrParts<T>")]
+pub struct CsrMatrix<T = f64> {
+    n_cols: usize,
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+/// Fields of a [`CsrMatrix`] before they are validated by [`CsrMatrix::new`].
+#[derive(Deserialize)]
+#[serde(bound = "T: Float")]
+struct CsrParts<T> {
+    n_cols: usize,
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Float> TryFrom<CsrParts<T>> for CsrMatrix<T> {
+    type Error = Error;
+
+    fn try_from(parts: CsrParts<T>) -> Result<Self> {
+        Self::new(parts.n_cols, parts.indptr, parts.indices, parts.values)
+    }
+}
+
+impl<T: Float> CsrMatrix<T> {
+    pub fn new(
+        n_cols: usize,
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Result<Self> {
+        if indices.len() != values.len() {
+            return Err(Error::DimensionMismatch(format!(
+                "{} indices but {} values",
+                indices.len(),
+                values.len()
+            )));
+        }
+        if indptr.first() != Some(&0)
+            || indptr.last() != Some(&indices.len())
+            || indptr.windows(2).any(|bounds| bounds[0] > bounds[1])
+        {
+            return Err(Error::DimensionMismatch(format!(
+                "indptr must increase from 0 to the number of entries {}",
+                indices.len()
+            )));
+        }
+        for (row, bounds) in indptr.windows(2).enumerate() {
+            let range = bounds[0]..bounds[1];
+            check_row(&indices[range.clone()], &values[range], Some(n_cols))
+                .map_err(|err| Error::DimensionMismatch(format!("row {row}: {err}")))?;
+        }
+        Ok(CsrMatrix {
+            n_cols,
+            indptr,
+            indices,
+            values,
+        })
+    }
+
+    /// Stacks sparse vectors with `n_cols` features.
+    pub fn from_rows(n_cols: usize, rows: &[SparseVector<T>]) -> Result<Self> {
+        for (i, row) in rows.iter().enumerate() {
+            check_row(&row.indices, &row.values, Some(n_cols))
+                .map_err(|err| Error::DimensionMismatch(format!("row {i}: {err}")))?;
+        }
+        Ok(Self::stack(n_cols, rows.iter().map(SparseVector::as_row)))
+    }
+
+    /// Keeps the non-zero entries of a dense matrix.
+    pub fn from_dense(x: &(impl Matrix<T> + ?Sized)) -> Self {
+        let rows: Vec<SparseVector<T>> = (0..x.n_rows())
+            .map(|i| SparseVector::from_dense(x.row(i)))
+            .collect();
+        Self::stack(x.n_cols(), rows.iter().map(SparseVector::as_row))
+    }
+
+    fn stack<'a>(n_cols: usize, rows: impl Iterator<Item = SparseRow<'a, T>>) -> Self {
+        let mut indptr = vec![0];
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        for row in rows {
+            indices.extend_from_slice(row.indices);
+            values.extend_from_slice(row.values);
+            indptr.push(indices.len());
+        }
+        CsrMatrix {
+            n_cols,
+            indptr,
+            indices,
+            values,
+        }
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.indptr.len() - 1
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    /// number of stored entries
+    pub fn nnz(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Offsets of the rows in [`CsrMatrix::indices`] and [`CsrMatrix::values`].
+    pub fn indptr(&self) -> &[usize] {
+        &self.indptr
+    }
+
+    /// Column indices of the stored entries.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Values of the stored entries.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn row(&self, i: usize) -> SparseRow<'_, T> {
+        let range = self.indptr[i]..self.indptr[i + 1];
+        SparseRow {
+            indices: &self.indices[range.clone()],
+            values: &self.values[range],
+        }
+    }
+
+    /// Matrix of the given rows.
+    pub fn select(&self, rows: &[usize]) -> CsrMatrix<T> {
+        Self::stack(self.n_cols, rows.iter().map(|&i| self.row(i)))
+    }
+}
+
+fn check_row<T>(indices: &[usize], values: &[T], n_cols: Option<usize>) -> Result<()> {
+    if indices.len() != values.len() {
+        return Err(Error::DimensionMismatch(format!(
+            "{} indices but {} values",
+            indices.len(),
+            values.len()
+        )));
+    }
+    if indices.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(Error::DimensionMismatch(
+            "indices must be strictly increasing".to_string(),
+        ));
+    }
+    match (indices.last(), n_cols) {
+        (Some(&index), Some(n_cols)) if index >= n_cols => Err(Error::DimensionMismatch(format!(
+            "index {index} is out of range for {n_cols} columns"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Dot product of two sparse vectors.
+pub fn dot<T: Float>(x: SparseRow<T>, y: SparseRow<T>) -> T {
+    let (mut i, mut j) = (0, 0);
+    let mut sum = T::zero();
+    while i < x.indices.len() && j < y.indices.len() {
+        match x.indices[i].cmp(&y.indices[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                sum += x.values[i] * y.values[j];
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    sum
+}
+
+/// Squared euclidean distance between two sparse vectors.
+pub fn squared_distance<T: Float>(x: SparseRow<T>, y: SparseRow<T>) -> T {
+    let (mut i, mut j) = (0, 0);
+    let mut sum = T::zero();
+    while i < x.indices.len() || j < y.indices.len() {
+        let x_index = x.indices.get(i).copied().unwrap_or(usize::MAX);
+        let y_index = y.indices.get(j).copied().unwrap_or(usize::MAX);
+        let difference = match x_index.cmp(&y_index) {
+            std::cmp::Ordering::Less => {
+                i += 1;
+                x.values[i - 1]
+            }
+            std::cmp::Ordering::Greater => {
+                j += 1;
+                y.values[j - 1]
+            }
+            std::cmp::Ordering::Equal => {
+                i += 1;
+                j += 1;
+                x.values[i - 1] - y.values[j - 1]
+            }
+        };
+        sum += difference * difference;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparse_vector_ops() {
+        let x = SparseVector::from_dense(&[1.0, 0.0, 2.0, 0.0, 3.0]);
+        let y = SparseVector::new(vec![1, 2, 3], vec![4.0, 5.0, 6.0]).unwrap();
+
+        assert_eq!(dot(x.as_row(), y.as_row()), 10.0);
+        assert_eq!(squared_distance(x.as_row(), y.as_row()), 71.0);
+        assert_eq!(y.as_row().to_dense(5), vec![0.0, 4.0, 5.0, 6.0, 0.0]);
+        assert!(SparseVector::new(vec![2, 1], vec![1.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn csr_matrix() {
+        let dense = vec![
+            vec![1.0, 0.0, 2.0],
+            vec![0.0, 0.0, 0.0],
+            vec![0.0, 3.0, 0.0],
+        ];
+        let x = CsrMatrix::from_dense(&dense);
+
+        assert_eq!(x.n_rows(), 3);
+        assert_eq!(x.n_cols(), 3);
+        assert_eq!(x.nnz(), 3);
+        assert_eq!(x.row(0).to_dense(3), dense[0]);
+        assert!(x.row(1).indices.is_empty());
+        assert_eq!(
+            x,
+            CsrMatrix::new(3, vec![0, 2, 2, 3], vec![0, 2, 1], vec![1.0, 2.0, 3.0]).unwrap()
+        );
+        assert_eq!(x.select(&[2, 0]).row(0).to_dense(3), dense[2]);
+
+        assert!(CsrMatrix::new(3, vec![0, 2], vec![0, 3], vec![1.0, 2.0]).is_err());
+        assert!(CsrMatrix::new(3, vec![0, 3], vec![0, 1], vec![1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn deserialize_validates() {
+        let x = CsrMatrix::new(3, vec![0, 2, 3], vec![0, 2, 1], vec![1.0, 2.0, 3.0]).unwrap();
+        let json = serde_json::to_string(&x).unwrap();
+        assert_eq!(serde_json::from_str::<CsrMatrix>(&json).unwrap(), x);
+
+        let invalid = [
+            r#"{"n_cols":3,"indptr":[],"indices":[],"values":[]}"#,
+            r#"{"n_cols":3,"indptr":[0,5],"indices":[0],"values":[1.0]}"#,
+            r#"{"n_cols":1,"indptr":[0,1],"indices":[4],"values":[1.0]}"#,
+        ];
+        for json in invalid {
+            assert!(serde_json::from_str::<CsrMatrix>(json).is_err(), "{json}");
+        }
+        assert!(
+            serde_json::from_str::<SparseVector>(r#"{"indices":[1,0],"values":[1.0,2.0]}"#)
+                .is_err()
+        );
+    }
+}
@@ -1,53 +1,56 @@
 use serde::{Deserialize, Serialize};
 
+use crate::float::Float;
+
 #[derive(Debug, Serialize, Deserialize)]
-pub struct SupportVector {
+#[serde(bound = "T: Float")]
+pub struct SupportVector<T = f64> {
     pub index: usize,
-    pub x: Vec<f64>,
-    pub alpha: f64,
-    pub grad: f64,
-    pub cmin: f64,
-    pub cmax: f64,
-    pub k: f64,
+    pub alpha: T,
+    pub grad: T,
+    pub cmin: T,
+    pub cmax: T,
+    pub k: T,
 }
 
-impl SupportVector {
-    pub fn new(index: usize, x: Vec<f64>, y: f64, grad: f64, c: f64, k: f64) -> SupportVector {
-        let (cmin, cmax) = if y > 0.0 { (0.0, c) } else { (-c, 0.0) };
+impl<T: Float> SupportVector<T> {
+    pub fn new(index: usize, y: T, grad: T, c: T, k: T) -> SupportVector<T> {
+        let (cmin, cmax) = if y > T::zero() {
+            (T::zero(), c)
+        } else {
+            (-c, T::zero())
+        };
+        SupportVector::with_bounds(index, grad, cmin, cmax, k)
+    }
+
+    /// Creates a support vector whose alpha is bounded by `cmin <= alpha <= cmax`.
+    pub fn with_bounds(index: usize, grad: T, cmin: T, cmax: T, k: T) -> SupportVector<T> {
         SupportVector {
             index,
-            x,
             grad,
             k,
-            alpha: 0.0,
+            alpha: T::zero(),
             cmin,
             cmax,
         }
     }
 }
 
-/// Convert a SupportVector into a vector of f64
-impl From<SupportVector> for Vec<f64> {
-    fn from(sv: SupportVector) -> Self {
-        sv.x
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn new_support_vector() {
-        let x = vec![1.0, 2.0, 3.0];
+        let index = 3;
         let y = 1.0;
         let grad = 0.1;
         let c = 1.0;
         let k = 0.5;
 
-        let sv = SupportVector::new(0, x.clone(), y, grad, c, k);
+        let sv = SupportVector::new(index, y, grad, c, k);
 
-        assert_eq!(x, sv.x);
+        assert_eq!(index, sv.index);
         assert_eq!(grad, sv.grad);
         assert_eq!(k, sv.k);
         assert_eq!(0.0, sv.cmin);
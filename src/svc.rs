@@ -1,111 +1,954 @@
 #[cfg(feature = "parallel")]
-use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    kernel::Kernel, optimizer::Optimizer, parameters::Parameters, smartcore_optimizer::SMO,
-    svm::SVM, B, W,
+    cache::CacheStats,
+    error::{Error, Result},
+    float::Float,
+    kernel::{
+        FeatureKernelSource, Kernel, KernelSource, KernelType, PrecomputedKernelSource,
+        SparseKernelSource,
+    },
+    libsvm::LibsvmModel,
+    matrix::Matrix,
+    nu_optimizer::NuSMO,
+    optimizer::{Convergence, ConvergenceStatus, ProgressHook},
+    parameters::{ClassWeight, Formulation, Parameters},
+    persistence::{self, BinaryReader, BinaryWriter, Header},
+    platt,
+    smartcore_optimizer::SMO,
+    sparse::{CsrMatrix, SparseRow, SparseVector},
+    svm::SVM,
+    validation, B, W,
 };
 
 #[derive(Serialize, Deserialize)]
-pub struct SVC {
-    parameters: Parameters,
-    alphas: Option<Vec<f64>>,
-    support_vectors: Option<Vec<Vec<f64>>>,
-    support_labels: Option<Vec<f64>>,
-    w: Option<W>,
-    b: Option<B>,
+#[serde(bound = "T: Float")]
+pub struct SVC<T: Float = f64> {
+    parameters: Parameters<T>,
+    /// dual variables of the support vectors, `|w_i|`
+    alphas: Option<Vec<T>>,
+    support_vectors: Option<Vec<Vec<T>>>,
+    /// original labels of the support vectors
+    support_labels: Option<Vec<i32>>,
+    /// training indices of the support vectors
+    support: Option<Vec<usize>>,
+    /// number of support vectors of the negative and the positive class
+    #[serde(default)]
+    n_support: Option<Vec<usize>>,
+    /// dual coefficients `y_i alpha_i` of the support vectors
+    w: Option<W<T>>,
+    b: Option<B<T>>,
+    /// Platt scaling parameters
+    prob_a: Option<f64>,
+    prob_b: Option<f64>,
+    /// number of features, or of training samples with a precomputed kernel
+    n_features: Option<usize>,
+    /// sorted labels of the negative and the positive class
+    #[serde(default)]
+    classes: Option<Vec<i32>>,
+    /// support vectors of a model trained on sparse samples
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sparse_support_vectors: Option<CsrMatrix<T>>,
+    /// primal weights of a model with a linear kernel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    coef: Option<Vec<T>>,
+    /// how the optimizer stopped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    convergence: Option<Convergence>,
+    #[serde(skip)]
+    progress: Option<ProgressHook>,
+    #[serde(skip)]
+    cache_stats: Option<CacheStats>,
 }
 
-unsafe impl Sync for SVC {}
-unsafe impl Send for SVC {}
+/// Training or test samples of an [`SVC`].
+#[derive(Clone, Copy)]
+enum Samples<'a, T: Float> {
+    Dense(&'a dyn Matrix<T>),
+    Sparse(&'a CsrMatrix<T>),
+}
 
-impl SVC {
-    pub fn new(parameters: Parameters) -> SVC {
+impl<T: Float> Samples<'_, T> {
+    fn n_rows(&self) -> usize {
+        match self {
+            Samples::Dense(x) => x.n_rows(),
+            Samples::Sparse(x) => x.n_rows(),
+        }
+    }
+}
+
+unsafe impl<T: Float> Sync for SVC<T> {}
+unsafe impl<T: Float> Send for SVC<T> {}
+
+impl<T: Float> SVC<T> {
+    pub fn new(parameters: Parameters<T>) -> SVC<T> {
         SVC {
             parameters,
             alphas: None,
             support_vectors: None,
             support_labels: None,
+            support: None,
+            n_support: None,
             w: None,
             b: None,
+            prob_a: None,
+            prob_b: None,
+            n_features: None,
+            classes: None,
+            sparse_support_vectors: None,
+            coef: None,
+            convergence: None,
+            progress: None,
+            cache_stats: None,
         }
     }
 
+    /// Sorted class labels seen during training, the second one is the positive class.
+    pub fn classes(&self) -> Option<&[i32]> {
+        self.classes.as_deref()
+    }
+
+    /// Training indices of the support vectors.
+    pub fn support_(&self) -> Option<&[usize]> {
+        self.support.as_deref()
+    }
+
+    /// Dual coefficients `y_i alpha_i` of the support vectors.
+    pub fn dual_coef_(&self) -> Option<&[T]> {
+        self.w.as_deref()
+    }
+
+    /// Number of support vectors of the negative and the positive class, in the order of
+    /// [`SVC::classes`].
+    pub fn n_support_(&self) -> Option<&[usize]> {
+        self.n_support.as_deref()
+    }
+
+    /// Original labels of the support vectors.
+    pub fn support_labels_(&self) -> Option<&[i32]> {
+        self.support_labels.as_deref()
+    }
+
+    /// Primal weights `sum_i w_i sv_i` of a model with a linear kernel.
+    pub fn coef_(&self) -> Option<&[T]> {
+        self.coef.as_deref()
+    }
+
+    /// Bias of the decision function.
+    pub fn intercept_(&self) -> Option<T> {
+        self.b
+    }
+
+    /// Reports the training progress to `progress`, the hook can cancel the training.
+    ///
+    /// A cancelled model keeps the solution found so far and is not calibrated for
+    /// probability estimates.
+    pub fn with_progress(&mut self, progress: ProgressHook) -> &mut Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Lookups of the kernel cache during training.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache_stats
+    }
+
+    /// How the optimizer stopped during training.
+    pub fn convergence(&self) -> Option<Convergence> {
+        self.convergence
+    }
+
+    fn is_precomputed(&self) -> bool {
+        self.parameters.precomputed
+    }
+
     pub fn predict_row(
-        x_i: &Vec<f64>,
-        w: &[f64],
-        support_vectors: &[Vec<f64>],
-        b: f64,
-        kernel: &Box<dyn Kernel>,
-    ) -> f64 {
+        x_i: &[T],
+        w: &[T],
+        support_vectors: &[Vec<T>],
+        b: T,
+        kernel: &dyn Kernel<T>,
+    ) -> T {
         #[cfg(feature = "parallel")]
-        let sum: f64 = w
+        let sum: T = w
             .par_iter()
             .zip(support_vectors.par_iter())
-            .map(|(w_i, support_vector)| w_i * kernel.compute(x_i, support_vector))
+            .map(|(&w_i, support_vector)| w_i * kernel.compute(x_i, support_vector))
             .sum();
 
         #[cfg(not(feature = "parallel"))]
-        let sum: f64 = w
+        let sum: T = w
             .iter()
             .zip(support_vectors.iter())
-            .map(|(w_i, support_vector)| w_i * kernel.compute(x_i, support_vector))
+            .map(|(&w_i, support_vector)| w_i * kernel.compute(x_i, support_vector))
             .sum();
         sum + b
     }
 
-    pub fn decision_function(&self, x: &Vec<Vec<f64>>) -> Vec<f64> {
-        let support_vectors = self.support_vectors.as_ref().expect("Model not trained");
-        let w = self.w.as_ref().expect("Model not trained");
-        let b = self.b.expect("Model not trained");
+    /// Decision value of a row of the kernel matrix between a test sample and the
+    /// training samples, `k_i` has a column for every training sample.
+    pub(crate) fn predict_precomputed_row(k_i: &[T], w: &[T], support: &[usize], b: T) -> T {
+        let sum: T = w
+            .iter()
+            .zip(support.iter())
+            .map(|(&w_i, &index)| w_i * k_i[index])
+            .sum();
+        sum + b
+    }
+
+    /// Decision value of a sparse sample against sparse support vectors.
+    pub fn predict_sparse_row(
+        x_i: SparseRow<T>,
+        w: &[T],
+        support_vectors: &CsrMatrix<T>,
+        b: T,
+        kernel: &dyn Kernel<T>,
+    ) -> T {
+        let sum: T = w
+            .iter()
+            .enumerate()
+            .map(|(j, &w_j)| w_j * kernel.compute_sparse(x_i, support_vectors.row(j)))
+            .sum();
+        sum + b
+    }
+
+    /// Computes the decision values of the samples in `x`.
+    ///
+    /// With a precomputed kernel `x` is the m×n kernel matrix between the m test samples
+    /// and the n training samples.
+    pub fn decision_function(&self, x: &(impl Matrix<T> + ?Sized)) -> Result<Vec<T>> {
+        self.decision_values(Samples::Dense(&x))
+    }
+
+    /// Computes the decision values of the sparse samples in `x`.
+    pub fn decision_function_sparse(&self, x: &CsrMatrix<T>) -> Result<Vec<T>> {
+        self.decision_values(Samples::Sparse(x))
+    }
+
+    /// Predicts the labels of the sparse samples in `x`.
+    pub fn predict_sparse(&self, x: &CsrMatrix<T>) -> Result<Vec<i32>> {
+        Ok(self.labels(&self.decision_function_sparse(x)?))
+    }
 
+    fn decision_values(&self, x: Samples<T>) -> Result<Vec<T>> {
+        let w = self.w.as_ref().ok_or(Error::NotFitted)?;
+        let b = self.b.ok_or(Error::NotFitted)?;
+        let kernel = &self.parameters.kernel;
+
+        match x {
+            Samples::Dense(x) => {
+                validation::check_predict_input(x, self.n_features)?;
+                if self.is_precomputed() {
+                    let support = self.support.as_ref().ok_or(Error::NotFitted)?;
+                    return Ok(Self::map_rows(x.n_rows(), |i| {
+                        Self::predict_precomputed_row(x.row(i), w, support, b)
+                    }));
+                }
+                if let Some(coef) = &self.coef {
+                    return Ok(Self::map_rows(x.n_rows(), |i| {
+                        let sum: T = x.row(i).iter().zip(coef.iter()).map(|(&x, &c)| x * c).sum();
+                        sum + b
+                    }));
+                }
+                if let Some(support_vectors) = &self.sparse_support_vectors {
+                    return Ok(Self::map_rows(x.n_rows(), |i| {
+                        let x_i = SparseVector::from_dense(x.row(i));
+                        Self::predict_sparse_row(x_i.as_row(), w, support_vectors, b, kernel)
+                    }));
+                }
+                let support_vectors = self.support_vectors.as_ref().ok_or(Error::NotFitted)?;
+                Ok(Self::map_rows(x.n_rows(), |i| {
+                    Self::predict_row(x.row(i), w, support_vectors, b, kernel)
+                }))
+            }
+            Samples::Sparse(x) => {
+                if self.is_precomputed() {
+                    return Err(Error::InvalidParameter(
+                        "sparse samples can not be used with a precomputed kernel".to_string(),
+                    ));
+                }
+                let n_features = self.n_features.unwrap_or(x.n_cols());
+                if x.n_cols() != n_features {
+                    return Err(Error::DimensionMismatch(format!(
+                        "x has {} features but the model was trained with {n_features}",
+                        x.n_cols()
+                    )));
+                }
+                if let Some(coef) = &self.coef {
+                    return Ok(Self::map_rows(x.n_rows(), |i| {
+                        let x_i = x.row(i);
+                        let sum: T = x_i
+                            .indices
+                            .iter()
+                            .zip(x_i.values.iter())
+                            .map(|(&index, &value)| value * coef[index])
+                            .sum();
+                        sum + b
+                    }));
+                }
+                if let Some(support_vectors) = &self.sparse_support_vectors {
+                    return Ok(Self::map_rows(x.n_rows(), |i| {
+                        Self::predict_sparse_row(x.row(i), w, support_vectors, b, kernel)
+                    }));
+                }
+                let support_vectors = self.support_vectors.as_ref().ok_or(Error::NotFitted)?;
+                Ok(Self::map_rows(x.n_rows(), |i| {
+                    let x_i = x.row(i).to_dense(n_features);
+                    Self::predict_row(&x_i, w, support_vectors, b, kernel)
+                }))
+            }
+        }
+    }
+
+    /// Collapses the dual coefficients of a linear model into `sum_i w_i sv_i`.
+    fn primal_coef(&self, w: &[T], n_features: usize) -> Vec<T> {
+        let mut coef = vec![T::zero(); n_features];
+        for (j, &w_j) in w.iter().enumerate() {
+            if let Some(support_vectors) = &self.support_vectors {
+                for (c, &x) in coef.iter_mut().zip(support_vectors[j].iter()) {
+                    *c += w_j * x;
+                }
+            }
+            if let Some(support_vectors) = &self.sparse_support_vectors {
+                let sv = support_vectors.row(j);
+                for (&index, &x) in sv.indices.iter().zip(sv.values.iter()) {
+                    coef[index] += w_j * x;
+                }
+            }
+        }
+        coef
+    }
+
+    fn map_rows(n_rows: usize, f: impl Fn(usize) -> T + Sync + Send) -> Vec<T> {
         #[cfg(feature = "parallel")]
-        let y: Vec<f64> = x
-            .par_iter()
-            .map(|sample| Self::predict_row(sample, w, support_vectors, b, &self.parameters.kernel))
-            .collect();
+        let y: Vec<T> = (0..n_rows).into_par_iter().map(f).collect();
         #[cfg(not(feature = "parallel"))]
-        let y: Vec<f64> = x
-            .iter()
-            .map(|sample| Self::predict_row(sample, w, support_vectors, b, &self.parameters.kernel))
-            .collect();
+        let y: Vec<T> = (0..n_rows).map(f).collect();
         y
     }
+
+    /// Maps decision values to the class labels.
+    fn labels(&self, decision_values: &[T]) -> Vec<i32> {
+        // models without stored classes were trained on -1 and 1
+        let (negative, positive) = match self.classes.as_deref() {
+            Some(&[negative, positive]) => (negative, positive),
+            _ => (-1, 1),
+        };
+        decision_values
+            .iter()
+            .map(|&y| if y > T::zero() { positive } else { negative })
+            .collect()
+    }
 }
 
-impl SVM for SVC {
-    fn fit(&mut self, x: &Vec<Vec<f64>>, y: &Vec<i32>) {
-        if x.len() != y.len() {
-            panic!("Number of samples in x does not match number of labels in y");
+impl<T: Float> SVC<T> {
+    /// Number of folds used to collect the decision values for Platt scaling.
+    const PROBABILITY_FOLDS: usize = 5;
+
+    /// Probability that each sample in `x` belongs to the positive class.
+    ///
+    /// Requires a model trained with `Parameters::probability` set.
+    pub fn predict_proba(&self, x: &(impl Matrix<T> + ?Sized)) -> Result<Vec<T>> {
+        self.probabilities(Samples::Dense(&x))
+    }
+
+    /// Probability that each sparse sample in `x` belongs to the positive class.
+    pub fn predict_proba_sparse(&self, x: &CsrMatrix<T>) -> Result<Vec<T>> {
+        self.probabilities(Samples::Sparse(x))
+    }
+
+    /// The sigmoid is fitted and evaluated in double precision.
+    fn probabilities(&self, x: Samples<T>) -> Result<Vec<T>> {
+        let (Some(a), Some(b)) = (self.prob_a, self.prob_b) else {
+            return Err(if self.w.is_some() {
+                Error::InvalidParameter(
+                    "model was trained without Parameters::probability".to_string(),
+                )
+            } else {
+                Error::NotFitted
+            });
+        };
+        Ok(self
+            .decision_values(x)?
+            .iter()
+            .map(|&f| T::cast(platt::sigmoid_predict(f.as_f64(), a, b)))
+            .collect())
+    }
+
+    /// Decision values of every training sample from models trained on the other folds.
+    fn cross_validated_decision_values(
+        &self,
+        x: Samples<T>,
+        y: &[i32],
+        weights: &[f64],
+    ) -> Result<Vec<f64>> {
+        let n = x.n_rows();
+        let precomputed = self.is_precomputed();
+        let permutation = SMO::permutate(n, Some(100));
+        let mut decision_values = vec![0.0; n];
+
+        // the class weights are already part of `weights`
+        let mut parameters = self.parameters.try_clone()?;
+        parameters
+            .with_probability(false)
+            .with_class_weight(ClassWeight::Uniform);
+
+        for fold in 0..Self::PROBABILITY_FOLDS {
+            let begin = fold * n / Self::PROBABILITY_FOLDS;
+            let end = (fold + 1) * n / Self::PROBABILITY_FOLDS;
+            let test = &permutation[begin..end];
+            let train: Vec<usize> = permutation[..begin]
+                .iter()
+                .chain(permutation[end..].iter())
+                .copied()
+                .collect();
+
+            let y_train: Vec<i32> = train.iter().map(|&i| y[i]).collect();
+            let weights_train: Vec<f64> = train.iter().map(|&i| weights[i]).collect();
+
+            let positives = y_train.iter().filter(|&&y_i| y_i > 0).count();
+            let fold_values = if positives == 0 || positives == y_train.len() {
+                // a single class in the training fold
+                let value = if positives > 0 { T::one() } else { -T::one() };
+                vec![if y_train.is_empty() { T::zero() } else { value }; test.len()]
+            } else {
+                let mut svc = SVC::new(parameters.try_clone()?);
+                match x {
+                    Samples::Dense(x) => {
+                        // with a precomputed kernel the columns are restricted to the
+                        // training fold
+                        let select = |rows: &[usize]| -> Vec<Vec<T>> {
+                            rows.iter()
+                                .map(|&i| {
+                                    if precomputed {
+                                        train.iter().map(|&j| x.row(i)[j]).collect()
+                                    } else {
+                                        x.row(i).to_vec()
+                                    }
+                                })
+                                .collect()
+                        };
+                        svc.fit_with_sample_weight(&select(&train), &y_train, &weights_train)?;
+                        svc.decision_function(&select(test))?
+                    }
+                    Samples::Sparse(x) => {
+                        svc.fit_sparse_with_sample_weight(
+                            &x.select(&train),
+                            &y_train,
+                            &weights_train,
+                        )?;
+                        svc.decision_function_sparse(&x.select(test))?
+                    }
+                }
+            };
+
+            for (&i, value) in test.iter().zip(fold_values) {
+                decision_values[i] = value.as_f64();
+            }
         }
 
-        let mut smo = SMO::new(
-            self.parameters.c,
-            self.parameters.tol,
-            self.parameters.epochs,
-        );
-        smo.with_seed(Some(100));
-        let (support_vectors, w, b) = smo.optimize(x, &y, &self.parameters.kernel);
+        Ok(decision_values)
+    }
+}
+
+impl<T: Float> SVC<T> {
+    /// Trains the model with a weight per sample, the box constraint of sample `i` is
+    /// `c * class_weight * sample_weight[i]`.
+    pub fn fit_with_sample_weight(
+        &mut self,
+        x: &(impl Matrix<T> + ?Sized),
+        y: &[i32],
+        sample_weight: &[f64],
+    ) -> Result<()> {
+        self.fit_weighted(Samples::Dense(&x), y, Some(sample_weight))
+    }
+
+    /// Trains the model on sparse samples, the support vectors are stored sparsely.
+    pub fn fit_sparse(&mut self, x: &CsrMatrix<T>, y: &[i32]) -> Result<()> {
+        self.fit_weighted(Samples::Sparse(x), y, None)
+    }
+
+    /// Trains the model on sparse samples with a weight per sample.
+    pub fn fit_sparse_with_sample_weight(
+        &mut self,
+        x: &CsrMatrix<T>,
+        y: &[i32],
+        sample_weight: &[f64],
+    ) -> Result<()> {
+        self.fit_weighted(Samples::Sparse(x), y, Some(sample_weight))
+    }
 
+    fn fit_weighted(
+        &mut self,
+        x: Samples<T>,
+        y: &[i32],
+        sample_weight: Option<&[f64]>,
+    ) -> Result<()> {
+        let precomputed = self.is_precomputed();
+        let n_features = match x {
+            Samples::Dense(x) => validation::check_fit_input(x, Some(y.len()), precomputed)?,
+            Samples::Sparse(_) if precomputed => {
+                return Err(Error::InvalidParameter(
+                    "sparse samples can not be used with a precomputed kernel".to_string(),
+                ))
+            }
+            Samples::Sparse(x) => {
+                validation::check_n_samples(x.n_rows(), Some(y.len()))?;
+                x.n_cols()
+            }
+        };
+        let mut weights = self.parameters.class_weight.sample_weights(y);
+        if let Some(sample_weight) = sample_weight {
+            validation::check_sample_weight(sample_weight, y.len())?;
+            for (weight, sample_weight) in weights.iter_mut().zip(sample_weight.iter()) {
+                *weight *= sample_weight;
+            }
+        }
+        validation::check_sample_weight(&weights, y.len())?;
+
+        let mut classes = y.to_vec();
+        classes.sort_unstable();
+        classes.dedup();
+        if classes.len() != 2 {
+            return Err(Error::InvalidLabel(format!(
+                "SVC requires exactly two classes, got {} (use MulticlassSVC for more)",
+                classes.len()
+            )));
+        }
+        // the optimizers work on the labels -1 and 1
+        let y: &[i32] = &y
+            .iter()
+            .map(|&label| if label == classes[1] { 1 } else { -1 })
+            .collect::<Vec<i32>>();
+        validation::check_c(self.parameters.c)?;
+
+        let source: Box<dyn KernelSource<T> + '_> = match x {
+            Samples::Dense(x) if precomputed => Box::new(PrecomputedKernelSource::new(x)),
+            Samples::Dense(x) => Box::new(FeatureKernelSource::new(x, &self.parameters.kernel)),
+            Samples::Sparse(x) => Box::new(SparseKernelSource::new(x, &self.parameters.kernel)),
+        };
+        let ((support, w, b), convergence, cache_stats) = match self.parameters.formulation {
+            Formulation::C => {
+                let mut smo = SMO::new(
+                    T::cast(self.parameters.c),
+                    T::cast(self.parameters.tol),
+                    self.parameters.epochs,
+                );
+                smo.with_seed(Some(100))
+                    .with_max_iter(self.parameters.max_iter)
+                    .with_max_time(self.parameters.max_time)
+                    .with_cache_size(self.parameters.cache_size)
+                    .with_progress(self.progress.take());
+                let weights: Vec<T> = weights.iter().map(|&weight| T::cast(weight)).collect();
+                let result = smo.optimize_weighted(source.as_ref(), y, &weights);
+                self.progress = smo.take_progress();
+                (result, smo.convergence(), smo.cache_stats())
+            }
+            Formulation::Nu => {
+                if weights.iter().any(|&weight| weight != 1.0) {
+                    return Err(Error::InvalidParameter(
+                        "class and sample weights are only supported by the C formulation"
+                            .to_string(),
+                    ));
+                }
+                validation::check_nu(self.parameters.nu)?;
+                let mut nu_smo = NuSMO::new(self.parameters.nu, self.parameters.tol);
+                nu_smo
+                    .with_max_iter(self.parameters.max_iter)
+                    .with_max_time(self.parameters.max_time)
+                    .with_cache_size(self.parameters.cache_size)
+                    .with_progress(self.progress.take());
+                let result = nu_smo.optimize(source.as_ref(), y);
+                self.progress = nu_smo.take_progress();
+                (result?, nu_smo.convergence(), nu_smo.cache_stats())
+            }
+        };
+
+        (self.support_vectors, self.sparse_support_vectors) = match x {
+            Samples::Dense(_) if precomputed => (None, None),
+            Samples::Dense(x) => (
+                Some(support.iter().map(|&i| x.row(i).to_vec()).collect()),
+                None,
+            ),
+            Samples::Sparse(x) => (None, Some(x.select(&support))),
+        };
+        self.coef = (self.parameters.kernel.type_of() == KernelType::Linear)
+            .then(|| self.primal_coef(&w, n_features));
+        let support_labels: Vec<i32> = support
+            .iter()
+            .map(|&i| if y[i] > 0 { classes[1] } else { classes[0] })
+            .collect();
+        let positives = support.iter().filter(|&&i| y[i] > 0).count();
+        self.n_support = Some(vec![support.len() - positives, positives]);
+        self.support_labels = Some(support_labels);
+        self.alphas = Some(w.iter().map(|w_i| w_i.abs()).collect());
+        self.support = Some(support);
+        self.convergence = convergence;
+        self.cache_stats = Some(cache_stats);
         self.w = Some(w);
-        self.support_vectors = Some(support_vectors);
         self.b = Some(b);
+        self.n_features = Some(n_features);
+        self.classes = Some(classes);
+
+        let cancelled = convergence.is_some_and(|c| c.status == ConvergenceStatus::Cancelled);
+        (self.prob_a, self.prob_b) = if self.parameters.probability && !cancelled {
+            let decision_values = self.cross_validated_decision_values(x, y, &weights)?;
+            let (a, b) = platt::sigmoid_train(&decision_values, y);
+            (Some(a), Some(b))
+        } else {
+            (None, None)
+        };
+        Ok(())
     }
+}
 
-    fn predict(&self, x: &Vec<Vec<f64>>) -> Vec<i32> {
-        let y_hat = self.decision_function(x);
-        y_hat
-            .iter()
-            .map(|&y| if y > 0.0 { 1 } else { -1 })
-            .collect()
+impl<T: Float> SVC<T> {
+    /// Saves the model to the file at `path`, see [`persistence`](crate::persistence).
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.save_to_writer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn save_to_writer(&self, writer: impl Write) -> Result<()> {
+        let header = Header::new(self.parameters.kernel.type_of(), self.n_features);
+        persistence::write(writer, header, self)
+    }
+
+    /// Loads a model saved with [`SVC::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::load_from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a model, fails with [`Error::Format`] on files of other versions of the
+    /// crate and on corrupted or inconsistent models.
+    pub fn load_from_reader(reader: impl Read) -> Result<Self> {
+        let (header, svc): (Header, Self) = persistence::read(reader)?;
+        let kernel = svc.parameters.kernel.type_of();
+        if header.kernel != kernel {
+            return Err(Error::Format(format!(
+                "header kernel {:?} does not match the model kernel {kernel:?}",
+                header.kernel
+            )));
+        }
+        if header.n_features != svc.n_features {
+            return Err(Error::Format(format!(
+                "header has {:?} features but the model {:?}",
+                header.n_features, svc.n_features
+            )));
+        }
+        svc.check_consistency().map_err(Error::Format)?;
+        Ok(svc)
+    }
+
+    /// Saves the model in the compact binary format, see
+    /// [`persistence`](crate::persistence).
+    pub fn save_binary(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.save_binary_to_writer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the model in the compact binary format. Only the parameters are stored as
+    /// JSON, since custom kernels can have arbitrary parameters.
+    pub fn save_binary_to_writer(&self, writer: impl Write) -> Result<()> {
+        let mut out = BinaryWriter::new::<T>(writer)?;
+        let parameters = serde_json::to_vec(&self.parameters)
+            .map_err(|err| Error::Format(format!("can not serialize the parameters: {err}")))?;
+        out.bytes(&parameters)?;
+        out.option(self.alphas.as_deref(), BinaryWriter::floats)?;
+        out.option(self.support_vectors.as_deref(), |out, vectors| {
+            out.usize(vectors.len())?;
+            vectors.iter().try_for_each(|vector| out.floats(vector))
+        })?;
+        out.option(self.support_labels.as_deref(), BinaryWriter::i32s)?;
+        out.option(self.support.as_deref(), BinaryWriter::usizes)?;
+        out.option(self.n_support.as_deref(), BinaryWriter::usizes)?;
+        out.option(self.w.as_deref(), BinaryWriter::floats)?;
+        out.option(self.b, BinaryWriter::float)?;
+        out.option(self.prob_a, BinaryWriter::f64)?;
+        out.option(self.prob_b, BinaryWriter::f64)?;
+        out.option(self.n_features, BinaryWriter::usize)?;
+        out.option(self.classes.as_deref(), BinaryWriter::i32s)?;
+        out.option(self.sparse_support_vectors.as_ref(), |out, vectors| {
+            out.usize(vectors.n_cols())?;
+            out.usizes(vectors.indptr())?;
+            out.usizes(vectors.indices())?;
+            out.floats(vectors.values())
+        })?;
+        out.option(self.coef.as_deref(), BinaryWriter::floats)?;
+        out.option(self.convergence, |out, convergence| {
+            out.u8(match convergence.status {
+                ConvergenceStatus::Converged => 0,
+                ConvergenceStatus::IterationLimit => 1,
+                ConvergenceStatus::TimeLimit => 2,
+                ConvergenceStatus::Cancelled => 3,
+            })?;
+            out.usize(convergence.iterations)?;
+            out.f64(convergence.kkt_violation)
+        })?;
+        out.finish()
+    }
+
+    /// Loads a model saved with [`SVC::save_binary`].
+    pub fn load_binary(path: impl AsRef<Path>) -> Result<Self> {
+        Self::load_binary_from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a model in the compact binary format, fails with [`Error::Format`] on files
+    /// of other versions of the crate, with other float types and on corrupted models.
+    pub fn load_binary_from_reader(reader: impl Read) -> Result<Self> {
+        let mut input = BinaryReader::new::<T>(reader)?;
+        let parameters = serde_json::from_slice(&input.bytes()?)
+            .map_err(|err| Error::Format(format!("can not read the parameters: {err}")))?;
+        let mut svc = SVC::new(parameters);
+        svc.alphas = input.option(BinaryReader::floats)?;
+        svc.support_vectors = input.option(|input| {
+            let n = input.usize()?;
+            (0..n).map(|_| input.floats()).collect()
+        })?;
+        svc.support_labels = input.option(BinaryReader::i32s)?;
+        svc.support = input.option(BinaryReader::usizes)?;
+        svc.n_support = input.option(BinaryReader::usizes)?;
+        svc.w = input.option(BinaryReader::floats)?;
+        svc.b = input.option(BinaryReader::float)?;
+        svc.prob_a = input.option(BinaryReader::f64)?;
+        svc.prob_b = input.option(BinaryReader::f64)?;
+        svc.n_features = input.option(BinaryReader::usize)?;
+        svc.classes = input.option(BinaryReader::i32s)?;
+        svc.sparse_support_vectors = input.option(|input| {
+            let n_cols = input.usize()?;
+            let (indptr, indices, values) = (input.usizes()?, input.usizes()?, input.floats()?);
+            CsrMatrix::new(n_cols, indptr, indices, values)
+                .map_err(|err| Error::Format(format!("sparse support vectors: {err}")))
+        })?;
+        svc.coef = input.option(BinaryReader::floats)?;
+        svc.convergence = input.option(|input| {
+            let status = match input.u8()? {
+                0 => ConvergenceStatus::Converged,
+                1 => ConvergenceStatus::IterationLimit,
+                2 => ConvergenceStatus::TimeLimit,
+                3 => ConvergenceStatus::Cancelled,
+                status => {
+                    return Err(Error::Format(format!(
+                        "invalid convergence status {status}"
+                    )))
+                }
+            };
+            Ok(Convergence {
+                status,
+                iterations: input.usize()?,
+                kkt_violation: input.f64()?,
+            })
+        })?;
+        input.finish()?;
+        svc.check_consistency().map_err(Error::Format)?;
+        Ok(svc)
+    }
+
+    /// Imports a model file of libsvm for samples with `n_features` features, see
+    /// [`libsvm`](crate::libsvm) and [`SVC::from_libsvm_model`].
+    pub fn load_libsvm_model(path: impl AsRef<Path>, n_features: usize) -> Result<Self> {
+        Self::from_libsvm_model(
+            LibsvmModel::read(BufReader::new(File::open(path)?))?,
+            n_features,
+        )
+    }
+
+    /// Exports the model as model file of libsvm.
+    pub fn save_libsvm_model(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.to_libsvm_model()?.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Converts a libsvm model for samples with `n_features` features, the hyperparameters
+    /// other than the kernel are not part of the model and keep their defaults.
+    ///
+    /// The number of features is not stored in the model, the support vectors only tell a
+    /// lower bound because features that are zero in every support vector are left out.
+    pub fn from_libsvm_model(model: LibsvmModel<T>, n_features: usize) -> Result<Self> {
+        let [first, second] = model.labels;
+        if first == second {
+            return Err(Error::Format(format!(
+                "both classes have the label {first}"
+            )));
+        }
+        // the first class of libsvm is the positive one, here it is the larger label
+        let sign = if first > second { T::one() } else { -T::one() };
+        let mut parameters = Parameters::new(model.kernel.new()?, 1.0, 1e-3, 2);
+        parameters
+            .with_formulation(model.formulation)
+            .with_probability(model.prob_a.is_some() && model.prob_b.is_some());
+        let support_vectors = CsrMatrix::from_rows(n_features, &model.support_vectors)?;
+        let w: Vec<T> = model.coef.iter().map(|&coef| sign * coef).collect();
+        let (first_count, second_count) = (model.n_sv[0], model.n_sv[1]);
+
+        let mut svc = SVC::new(parameters);
+        svc.support_labels = Some(
+            (0..w.len())
+                .map(|i| if i < first_count { first } else { second })
+                .collect(),
+        );
+        svc.n_support = Some(if first > second {
+            vec![second_count, first_count]
+        } else {
+            vec![first_count, second_count]
+        });
+        svc.alphas = Some(w.iter().map(|w_i| w_i.abs()).collect());
+        svc.b = Some(-sign * model.rho);
+        (svc.prob_a, svc.prob_b) = match (model.prob_a, model.prob_b) {
+            (Some(a), Some(b)) => (Some(a), Some(sign.as_f64() * b)),
+            _ => (None, None),
+        };
+        svc.n_features = Some(n_features);
+        svc.classes = Some(vec![first.min(second), first.max(second)]);
+        svc.sparse_support_vectors = Some(support_vectors);
+        svc.coef = (model.kernel == KernelType::Linear).then(|| svc.primal_coef(&w, n_features));
+        svc.w = Some(w);
+        svc.check_consistency().map_err(Error::Format)?;
+        Ok(svc)
+    }
+
+    /// Converts the model to a libsvm model, requires a linear or RBF kernel.
+    pub fn to_libsvm_model(&self) -> Result<LibsvmModel<T>> {
+        let w = self.w.as_ref().ok_or(Error::NotFitted)?;
+        let b = self.b.ok_or(Error::NotFitted)?;
+        let support_vectors: Vec<SparseVector<T>> =
+            match (&self.support_vectors, &self.sparse_support_vectors) {
+                (Some(vectors), _) => vectors
+                    .iter()
+                    .map(|v| SparseVector::from_dense(v))
+                    .collect(),
+                (None, Some(vectors)) => (0..vectors.n_rows())
+                    .map(|i| {
+                        let row = vectors.row(i);
+                        SparseVector::new(row.indices.to_vec(), row.values.to_vec())
+                    })
+                    .collect::<Result<_>>()?,
+                (None, None) => {
+                    return Err(Error::Format(
+                        "a model with a precomputed kernel can not be exported".to_string(),
+                    ))
+                }
+            };
+        let (negative, positive) = match self.classes.as_deref() {
+            Some(&[negative, positive]) => (negative, positive),
+            _ => (-1, 1),
+        };
+        // libsvm lists the support vectors of the first, positive class first
+        let (positives, negatives): (Vec<usize>, Vec<usize>) =
+            (0..w.len()).partition(|&i| w[i] > T::zero());
+        let order: Vec<usize> = positives.iter().chain(&negatives).copied().collect();
+        Ok(LibsvmModel {
+            formulation: self.parameters.formulation,
+            kernel: self.parameters.kernel.type_of(),
+            labels: [positive, negative],
+            rho: -b,
+            prob_a: self.prob_a,
+            prob_b: self.prob_b,
+            n_sv: [positives.len(), negatives.len()],
+            coef: order.iter().map(|&i| w[i]).collect(),
+            support_vectors: order.iter().map(|&i| support_vectors[i].clone()).collect(),
+        })
+    }
+
+    fn check_consistency(&self) -> std::result::Result<(), String> {
+        let n_support = self.w.as_ref().map(Vec::len);
+        let lengths = [
+            ("alphas", self.alphas.as_ref().map(Vec::len)),
+            ("support", self.support.as_ref().map(Vec::len)),
+            ("support labels", self.support_labels.as_ref().map(Vec::len)),
+            (
+                "support vectors",
+                self.support_vectors.as_ref().map(Vec::len),
+            ),
+            (
+                "sparse support vectors",
+                self.sparse_support_vectors.as_ref().map(CsrMatrix::n_rows),
+            ),
+        ];
+        for (name, length) in lengths {
+            if let (Some(n), Some(length)) = (n_support, length) {
+                if n != length {
+                    return Err(format!("{length} {name} but {n} dual coefficients"));
+                }
+            }
+        }
+        if let (Some(n_support), Some(counts)) = (n_support, &self.n_support) {
+            if counts.iter().sum::<usize>() != n_support {
+                return Err(format!(
+                    "support vector counts {counts:?} do not add up to {n_support}"
+                ));
+            }
+        }
+        if let Some(n_features) = self.n_features {
+            if let (false, Some(vectors)) = (self.is_precomputed(), &self.support_vectors) {
+                if let Some(row) = vectors.iter().position(|v| v.len() != n_features) {
+                    return Err(format!(
+                        "support vector {row} has {} features, expected {n_features}",
+                        vectors[row].len()
+                    ));
+                }
+            }
+            if let Some(vectors) = &self.sparse_support_vectors {
+                if vectors.n_cols() != n_features {
+                    return Err(format!(
+                        "sparse support vectors have {} features, expected {n_features}",
+                        vectors.n_cols()
+                    ));
+                }
+            }
+            if let Some(coef) = &self.coef {
+                if coef.len() != n_features {
+                    return Err(format!("{} weights, expected {n_features}", coef.len()));
+                }
+            }
+        }
+        if let Some(classes) = &self.classes {
+            if classes.len() != 2 {
+                return Err(format!("expected 2 classes, got {classes:?}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Float> SVM<T> for SVC<T> {
+    /// Trains the model. With a precomputed kernel `x` is the n×n Gram matrix of the
+    /// training samples.
+    fn fit(&mut self, x: &(impl Matrix<T> + ?Sized), y: &[i32]) -> Result<()> {
+        self.fit_weighted(Samples::Dense(&x), y, None)
+    }
+
+    fn predict(&self, x: &(impl Matrix<T> + ?Sized)) -> Result<Vec<i32>> {
+        Ok(self.labels(&self.decision_function(x)?))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use super::*;
-    use crate::kernel::{KernelType, LinearKernel, RBFKernel};
+    use crate::{
+        kernel::{KernelType, LinearKernel, RBFKernel, SigmoidKernel},
+        optimizer::{Progress, Signal},
+        test_utils::{blobs, grid, iris_binary, TempFile},
+    };
 
     #[test]
     fn it_works() {
@@ -116,37 +959,16 @@ mod tests {
         let x = vec![vec![1.0], vec![2.0], vec![3.0]];
         let y = vec![-1, -1, 1];
 
-        svc.fit(&x, &y);
+        svc.fit(&x, &y).unwrap();
 
-        let predictions = svc.predict(&x);
+        let predictions = svc.predict(&x).unwrap();
 
         assert_eq!(predictions.len(), x.len());
     }
 
     #[test]
     fn svc_fit_predict_rbf() {
-        let x = vec![
-            vec![5.1, 3.5, 1.4, 0.2],
-            vec![4.9, 3.0, 1.4, 0.2],
-            vec![4.7, 3.2, 1.3, 0.2],
-            vec![4.6, 3.1, 1.5, 0.2],
-            vec![5.0, 3.6, 1.4, 0.2],
-            vec![5.4, 3.9, 1.7, 0.4],
-            vec![4.6, 3.4, 1.4, 0.3],
-            vec![5.0, 3.4, 1.5, 0.2],
-            vec![4.4, 2.9, 1.4, 0.2],
-            vec![4.9, 3.1, 1.5, 0.1],
-            vec![7.0, 3.2, 4.7, 1.4],
-            vec![6.4, 3.2, 4.5, 1.5],
-            vec![6.9, 3.1, 4.9, 1.5],
-            vec![5.5, 2.3, 4.0, 1.3],
-            vec![6.5, 2.8, 4.6, 1.5],
-            vec![5.7, 2.8, 4.5, 1.3],
-            vec![6.3, 3.3, 4.7, 1.6],
-            vec![4.9, 2.4, 3.3, 1.0],
-            vec![6.6, 2.9, 4.6, 1.3],
-            vec![5.2, 2.7, 3.9, 1.4],
-        ];
+        let (x, _) = iris_binary();
         let y: Vec<i32> = vec![
             -1, -1, -1, -1, -1, -1, -1, -1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
         ];
@@ -155,9 +977,9 @@ mod tests {
         parameters.with_kernel(Box::new(RBFKernel::new(0.7)));
         let mut svc = SVC::new(parameters);
 
-        svc.fit(&x, &y);
+        svc.fit(&x, &y).unwrap();
 
-        let predictions = svc.predict(&x);
+        let predictions = svc.predict(&x).unwrap();
 
         let correct_predictions = predictions
             .iter()
@@ -172,31 +994,9 @@ mod tests {
         );
     }
 
-    #[ignore = "does not stop"]
     #[test]
     fn svc_fit_predict_linear() {
-        let x = vec![
-            vec![5.1, 3.5, 1.4, 0.2],
-            vec![4.9, 3.0, 1.4, 0.2],
-            vec![4.7, 3.2, 1.3, 0.2],
-            vec![4.6, 3.1, 1.5, 0.2],
-            vec![5.0, 3.6, 1.4, 0.2],
-            vec![5.4, 3.9, 1.7, 0.4],
-            vec![4.6, 3.4, 1.4, 0.3],
-            vec![5.0, 3.4, 1.5, 0.2],
-            vec![4.4, 2.9, 1.4, 0.2],
-            vec![4.9, 3.1, 1.5, 0.1],
-            vec![7.0, 3.2, 4.7, 1.4],
-            vec![6.4, 3.2, 4.5, 1.5],
-            vec![6.9, 3.1, 4.9, 1.5],
-            vec![5.5, 2.3, 4.0, 1.3],
-            vec![6.5, 2.8, 4.6, 1.5],
-            vec![5.7, 2.8, 4.5, 1.3],
-            vec![6.3, 3.3, 4.7, 1.6],
-            vec![4.9, 2.4, 3.3, 1.0],
-            vec![6.6, 2.9, 4.6, 1.3],
-            vec![5.2, 2.7, 3.9, 1.4],
-        ];
+        let (x, _) = iris_binary();
         let y: Vec<i32> = vec![
             -1, -1, -1, -1, -1, -1, -1, -1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
         ];
@@ -210,9 +1010,9 @@ mod tests {
 
         let mut svc = SVC::new(parameters);
 
-        svc.fit(&x, &y);
+        svc.fit(&x, &y).unwrap();
 
-        let predictions = svc.predict(&x);
+        let predictions = svc.predict(&x).unwrap();
 
         let correct_predictions = predictions
             .iter()
@@ -227,9 +1027,115 @@ mod tests {
         );
     }
 
+    #[test]
+    fn svc_fit_predict_nu() {
+        let (x, y) = iris_binary();
+
+        let mut parameters = Parameters::default();
+        parameters
+            .with_kernel(Box::new(RBFKernel::new(0.7)))
+            .with_formulation(Formulation::Nu)
+            .with_nu(0.3);
+        let mut svc = SVC::new(parameters);
+
+        svc.fit(&x, &y).unwrap();
+
+        let predictions = svc.predict(&x).unwrap();
+
+        let correct_predictions = predictions
+            .iter()
+            .zip(y.iter())
+            .filter(|(&pred, &true_label)| pred == true_label)
+            .count();
+        let accuracy = correct_predictions as f64 / x.len() as f64;
+
+        assert!(
+            accuracy >= 0.9,
+            "Accuracy ({accuracy}) is not larger or equal to 0.9"
+        );
+        assert!(svc.support.as_ref().unwrap().len() >= 6);
+    }
+
+    #[test]
+    fn svc_predict_proba() {
+        let (x, y) = iris_binary();
+
+        let mut parameters = Parameters::default();
+        parameters
+            .with_kernel(Box::new(RBFKernel::new(0.7)))
+            .with_probability(true);
+        let mut svc = SVC::new(parameters);
+        svc.fit(&x, &y).unwrap();
+
+        let probabilities = svc.predict_proba(&x).unwrap();
+        assert!(probabilities.iter().all(|&p| p > 0.0 && p < 1.0));
+        let decision_values = svc.decision_function(&x).unwrap();
+        for (p, f) in probabilities.iter().zip(decision_values.iter()) {
+            assert_eq!(*p > 0.5, *f > -svc.prob_b.unwrap() / svc.prob_a.unwrap());
+        }
+        assert!(probabilities[0] < 0.5);
+        assert!(probabilities[10] > 0.5);
+
+        let json = serde_json::to_string(&svc).unwrap();
+        let loaded: SVC = serde_json::from_str(&json).unwrap();
+        for (loaded, p) in loaded
+            .predict_proba(&x)
+            .unwrap()
+            .iter()
+            .zip(probabilities.iter())
+        {
+            assert!((loaded - p).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn svc_fit_predict_precomputed() {
+        // five samples of each class for training and one of each for testing
+        let (iris, labels) = iris_binary();
+        let x = [&iris[..5], &iris[10..15]].concat();
+        let y = [&labels[..5], &labels[10..15]].concat();
+        let x_test = vec![iris[5].clone(), iris[16].clone()];
+
+        let kernel = RBFKernel::new(0.7);
+        let gram: Vec<Vec<f64>> = x
+            .iter()
+            .map(|x_i| x.iter().map(|x_j| kernel.compute(x_i, x_j)).collect())
+            .collect();
+        let k_test: Vec<Vec<f64>> = x_test
+            .iter()
+            .map(|x_i| x.iter().map(|x_j| kernel.compute(x_i, x_j)).collect())
+            .collect();
+
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(Box::new(RBFKernel::new(0.7)));
+        let mut svc = SVC::new(parameters);
+        svc.fit(&x, &y).unwrap();
+
+        let mut parameters = Parameters::default();
+        parameters.with_precomputed(true);
+        let mut precomputed = SVC::new(parameters);
+        precomputed.fit(&gram, &y).unwrap();
+
+        let expected = svc.decision_function(&x_test).unwrap();
+        let result = precomputed.decision_function(&k_test).unwrap();
+        for (expected, result) in expected.iter().zip(result.iter()) {
+            assert!((expected - result).abs() < 1e-12);
+        }
+        assert_eq!(precomputed.predict(&k_test).unwrap(), vec![-1, 1]);
+
+        let mut buffer = Vec::new();
+        precomputed.save_to_writer(&mut buffer).unwrap();
+        let loaded = SVC::<f64>::load_from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(loaded.predict(&k_test).unwrap(), vec![-1, 1]);
+        assert!(matches!(
+            loaded.predict(&x_test),
+            Err(Error::DimensionMismatch(_))
+        ));
+    }
+
     #[test]
     fn test_predict_w_b() {
-        let x = vec![vec![1.0, 1.0], vec![2.0, 2.0], vec![3.0, 3.0]];
+        let x = [vec![1.0, 1.0], vec![2.0, 2.0], vec![3.0, 3.0]];
         let support_vectors = vec![vec![1.0, 1.0], vec![2.0, 2.0]];
 
         let w = vec![1.0];
@@ -244,6 +1150,19 @@ mod tests {
         assert_eq!(result, 4.0);
     }
 
+    #[test]
+    fn test_predict_row_sigmoid() {
+        let support_vectors = vec![vec![1.0, 1.0], vec![-1.0, -1.0]];
+
+        let w = vec![1.0, -1.0];
+        let b = 0.0;
+
+        let kernel: Box<dyn Kernel> = Box::new(SigmoidKernel::new(0.5, 0.0));
+
+        let result = SVC::predict_row(&[1.0, 1.0], &w, &support_vectors, b, &kernel);
+        assert!((result - 2.0 * 1f64.tanh()).abs() < 1e-12);
+    }
+
     #[test]
     fn test_decision_function() {
         let x = vec![vec![1.0, 1.0], vec![2.0, 2.0], vec![3.0, 3.0]];
@@ -262,11 +1181,22 @@ mod tests {
             alphas: None,
             support_vectors: Some(support_vectors),
             support_labels: None,
+            support: None,
+            n_support: None,
             w: Some(w),
             b: Some(b),
+            prob_a: None,
+            prob_b: None,
+            n_features: None,
+            classes: None,
+            sparse_support_vectors: None,
+            coef: None,
+            convergence: None,
+            progress: None,
+            cache_stats: None,
         };
 
-        let result = svc.decision_function(&x);
+        let result = svc.decision_function(&x).unwrap();
         assert_eq!(result, vec![2.0, 4.0, 6.0]);
     }
 
@@ -288,11 +1218,568 @@ mod tests {
             alphas: None,
             support_vectors: Some(support_vectors),
             support_labels: None,
+            support: None,
+            n_support: None,
             w: Some(w),
             b: Some(b),
+            prob_a: None,
+            prob_b: None,
+            n_features: None,
+            classes: None,
+            sparse_support_vectors: None,
+            coef: None,
+            convergence: None,
+            progress: None,
+            cache_stats: None,
         };
 
-        let result = svc.predict(&x);
+        let result = svc.predict(&x).unwrap();
         assert_eq!(result, vec![1, 1, 1]);
     }
+
+    #[test]
+    fn test_errors() {
+        let x = vec![vec![1.0, 1.0], vec![2.0, 2.0], vec![-1.0, -1.0]];
+        let mut svc = SVC::new(Parameters::default());
+
+        assert!(matches!(svc.predict(&x), Err(Error::NotFitted)));
+        assert!(matches!(
+            svc.fit(&x, &[1, -1]),
+            Err(Error::DimensionMismatch(_))
+        ));
+        assert!(matches!(svc.fit(&Vec::new(), &[]), Err(Error::EmptyInput)));
+        assert!(matches!(
+            svc.fit(&x, &[1, 2, -1]),
+            Err(Error::InvalidLabel(_))
+        ));
+        assert!(matches!(
+            svc.fit(&x, &[1, 1, 1]),
+            Err(Error::InvalidLabel(_))
+        ));
+
+        svc.fit(&x, &[1, 1, -1]).unwrap();
+        assert!(matches!(
+            svc.predict(&[vec![1.0, 2.0, 3.0]]),
+            Err(Error::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn svc_fit_predict_arbitrary_labels() {
+        let x = vec![
+            vec![1.0, 1.0],
+            vec![1.5, 2.0],
+            vec![2.0, 1.5],
+            vec![-1.0, -1.0],
+            vec![-1.5, -2.0],
+            vec![-2.0, -1.5],
+        ];
+        let y = vec![7, 7, 7, 0, 0, 0];
+        let mut svc = SVC::new(Parameters::default());
+
+        svc.fit(&x, &y).unwrap();
+
+        assert_eq!(svc.classes(), Some(&[0, 7][..]));
+        assert_eq!(svc.predict(&x).unwrap(), y);
+
+        let json = serde_json::to_string(&svc).unwrap();
+        let loaded: SVC = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.predict(&x).unwrap(), y);
+
+        let mut svc = SVC::new(Parameters::default());
+        let result = svc.fit(&x, &[7, 7, 3, 0, 0, 0]);
+        assert!(matches!(result, Err(Error::InvalidLabel(_))));
+    }
+
+    #[test]
+    fn svc_fit_predict_weighted() {
+        // the two positive samples overlap the negative class and are only recovered
+        // when their margin errors are weighted higher
+        let mut x: Vec<Vec<f64>> = (0..12)
+            .map(|i| vec![i as f64 * 0.1, ((i * 7) % 5) as f64 * 0.1])
+            .collect();
+        x.extend([vec![1.0, 0.2], vec![1.05, 0.3]]);
+        let mut y = vec![0; 12];
+        y.extend([1, 1]);
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(KernelType::linear());
+
+        let mut svc = SVC::new(parameters.try_clone().unwrap());
+        svc.fit(&x, &y).unwrap();
+        assert_eq!(svc.predict(&x[12..]).unwrap(), vec![0, 0]);
+
+        let mut balanced = parameters.try_clone().unwrap();
+        balanced.with_class_weight(ClassWeight::Balanced);
+        let mut svc = SVC::new(balanced);
+        svc.fit(&x, &y).unwrap();
+        assert_eq!(svc.predict(&x[12..]).unwrap(), vec![1, 1]);
+        assert_eq!(svc.predict(&x[..3]).unwrap(), vec![0, 0, 0]);
+
+        let mut svc = SVC::new(parameters);
+        let mut sample_weight = vec![1.0; 12];
+        sample_weight.extend([10.0, 10.0]);
+        svc.fit_with_sample_weight(&x, &y, &sample_weight).unwrap();
+        assert_eq!(svc.predict(&x[12..]).unwrap(), vec![1, 1]);
+        assert_eq!(svc.predict(&x[..3]).unwrap(), vec![0, 0, 0]);
+
+        assert!(matches!(
+            svc.fit_with_sample_weight(&x, &y, &[1.0; 3]),
+            Err(Error::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            svc.fit_with_sample_weight(&x, &y, &[0.0; 14]),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn svc_fit_predict_dense_matrix() {
+        let data = [
+            1.0, 1.0, 1.5, 2.0, 2.0, 1.5, -1.0, -1.0, -1.5, -2.0, -2.0, -1.5,
+        ];
+        let x = crate::matrix::DenseMatrix::new(&data, 6, 2).unwrap();
+        let y = vec![1, 1, 1, -1, -1, -1];
+        let mut svc = SVC::new(Parameters::default());
+
+        svc.fit(&x, &y).unwrap();
+
+        assert_eq!(svc.predict(&x).unwrap(), y);
+        let rows: Vec<Vec<f64>> = data.chunks(2).map(|row| row.to_vec()).collect();
+        assert_eq!(
+            svc.decision_function(&x).unwrap(),
+            svc.decision_function(&rows).unwrap()
+        );
+    }
+
+    #[test]
+    fn svc_fit_predict_sparse() {
+        let x = vec![
+            vec![1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            vec![1.5, 0.0, 0.0, 2.0, 0.0, 0.0],
+            vec![2.0, 0.0, 0.0, 1.5, 0.0, 0.0],
+            vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+            vec![0.0, 0.0, 1.5, 0.0, 0.0, 2.0],
+            vec![0.0, 0.0, 2.0, 0.0, 0.0, 1.5],
+        ];
+        let y = vec![1, 1, 1, 0, 0, 0];
+        let x_sparse = CsrMatrix::from_dense(&x);
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(Box::new(RBFKernel::new(0.5)));
+
+        let mut dense = SVC::new(parameters.try_clone().unwrap());
+        dense.fit(&x, &y).unwrap();
+        let mut sparse = SVC::new(parameters);
+        sparse.fit_sparse(&x_sparse, &y).unwrap();
+
+        assert!(sparse.support_vectors.is_none());
+        assert!(sparse.sparse_support_vectors.is_some());
+        assert_eq!(sparse.predict_sparse(&x_sparse).unwrap(), y);
+        assert_eq!(sparse.predict(&x).unwrap(), y);
+        assert_eq!(dense.predict_sparse(&x_sparse).unwrap(), y);
+        let expected = dense.decision_function(&x).unwrap();
+        for (d, s) in expected
+            .iter()
+            .zip(sparse.decision_function_sparse(&x_sparse).unwrap())
+        {
+            assert!((d - s).abs() < 1e-9);
+        }
+
+        let json = serde_json::to_string(&sparse).unwrap();
+        let loaded: SVC = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.predict_sparse(&x_sparse).unwrap(), y);
+        assert!(!serde_json::to_string(&dense)
+            .unwrap()
+            .contains("sparse_support_vectors"));
+    }
+
+    #[test]
+    fn svc_fit_predict_f32() {
+        let x64 = vec![
+            vec![1.0, 1.0],
+            vec![1.5, 2.0],
+            vec![2.0, 1.5],
+            vec![-1.0, -1.0],
+            vec![-1.5, -2.0],
+            vec![-2.0, -1.5],
+        ];
+        let x32: Vec<Vec<f32>> = x64
+            .iter()
+            .map(|row| row.iter().map(|&v| v as f32).collect())
+            .collect();
+        let y = vec![1, 1, 1, -1, -1, -1];
+
+        let mut svc64 = SVC::new(Parameters::new(Box::new(RBFKernel::new(0.5)), 1.0, 1e-3, 2));
+        svc64.fit(&x64, &y).unwrap();
+        let mut svc32 = SVC::new(Parameters::<f32>::new(
+            Box::new(RBFKernel::new(0.5)),
+            1.0,
+            1e-3,
+            2,
+        ));
+        svc32.fit(&x32, &y).unwrap();
+
+        assert_eq!(svc32.predict(&x32).unwrap(), y);
+        let expected = svc64.decision_function(&x64).unwrap();
+        for (d64, d32) in expected.iter().zip(svc32.decision_function(&x32).unwrap()) {
+            assert!((d64 - d32 as f64).abs() < 1e-3);
+        }
+
+        let json = serde_json::to_string(&svc32).unwrap();
+        let loaded: SVC<f32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.predict(&x32).unwrap(), y);
+    }
+
+    #[test]
+    fn svc_linear_coef() {
+        let x = vec![
+            vec![1.0, 1.0, 0.0],
+            vec![1.5, 2.0, 0.0],
+            vec![2.0, 1.5, 0.5],
+            vec![-1.0, -1.0, 0.0],
+            vec![-1.5, -2.0, 0.5],
+            vec![-2.0, -1.5, 0.0],
+        ];
+        let y = vec![1, 1, 1, -1, -1, -1];
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(KernelType::linear());
+        let mut svc = SVC::new(parameters.try_clone().unwrap());
+        svc.fit(&x, &y).unwrap();
+
+        let coef = svc.coef_().unwrap();
+        assert_eq!(coef.len(), 3);
+        assert_eq!(svc.intercept_(), svc.b);
+        let kernel = LinearKernel::new();
+        for (x_i, decision) in x.iter().zip(svc.decision_function(&x).unwrap()) {
+            let expected = SVC::predict_row(
+                x_i,
+                svc.w.as_ref().unwrap(),
+                svc.support_vectors.as_ref().unwrap(),
+                svc.b.unwrap(),
+                &kernel,
+            );
+            assert!((decision - expected).abs() < 1e-9);
+        }
+
+        let x_sparse = CsrMatrix::from_dense(&x);
+        let mut sparse = SVC::new(parameters);
+        sparse.fit_sparse(&x_sparse, &y).unwrap();
+        for (c, s) in coef.iter().zip(sparse.coef_().unwrap()) {
+            assert!((c - s).abs() < 1e-9);
+        }
+        assert_eq!(sparse.predict_sparse(&x_sparse).unwrap(), y);
+
+        let mut rbf = SVC::new(Parameters::default());
+        rbf.fit(&x, &y).unwrap();
+        assert!(rbf.coef_().is_none());
+        assert!(rbf.intercept_().is_some());
+    }
+
+    #[test]
+    fn svc_convergence() {
+        let (x, y) = blobs([1, -1]);
+
+        for formulation in [Formulation::C, Formulation::Nu] {
+            let mut parameters = Parameters::default();
+            parameters.with_formulation(formulation);
+            let mut svc = SVC::new(parameters.try_clone().unwrap());
+            svc.fit(&x, &y).unwrap();
+            let convergence = svc.convergence().unwrap();
+            assert_eq!(convergence.status, ConvergenceStatus::Converged);
+            assert!(convergence.iterations > 0);
+            assert!(convergence.kkt_violation <= 1e-3);
+
+            let mut svc = SVC::new(parameters.try_clone().unwrap());
+            svc.parameters.with_max_iter(1);
+            svc.fit(&x, &y).unwrap();
+            let convergence = svc.convergence().unwrap();
+            assert_eq!(convergence.status, ConvergenceStatus::IterationLimit);
+            assert_eq!(convergence.iterations, 1);
+
+            let mut svc = SVC::new(parameters);
+            svc.parameters.with_max_time(Duration::ZERO);
+            svc.fit(&x, &y).unwrap();
+            assert_eq!(
+                svc.convergence().unwrap().status,
+                ConvergenceStatus::TimeLimit
+            );
+            assert_eq!(svc.predict(&x).unwrap().len(), x.len());
+        }
+    }
+
+    #[test]
+    fn svc_progress() {
+        let (x, y) = grid();
+
+        for formulation in [Formulation::C, Formulation::Nu] {
+            let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::<Progress>::new()));
+            let seen = reports.clone();
+            let mut parameters = Parameters::default();
+            parameters.with_formulation(formulation).with_nu(0.3);
+            let mut svc = SVC::new(parameters);
+            svc.with_progress(ProgressHook::new(2, move |progress| {
+                seen.lock().unwrap().push(*progress);
+                if progress.iterations >= 4 {
+                    Signal::Cancel
+                } else {
+                    Signal::Continue
+                }
+            }));
+            svc.fit(&x, &y).unwrap();
+
+            let reports = reports.lock().unwrap();
+            assert_eq!(
+                reports.iter().map(|p| p.iterations).collect::<Vec<_>>(),
+                vec![2, 4]
+            );
+            assert!(reports
+                .iter()
+                .all(|p| p.n_support > 0 && p.elapsed.is_some()));
+            let convergence = svc.convergence().unwrap();
+            assert_eq!(convergence.status, ConvergenceStatus::Cancelled);
+            assert_eq!(convergence.iterations, 4);
+            assert_eq!(svc.predict(&x).unwrap().len(), x.len());
+        }
+    }
+
+    #[test]
+    fn svc_cache_size() {
+        let (x, y) = grid();
+
+        let mut svc = SVC::new(Parameters::default());
+        svc.fit(&x, &y).unwrap();
+        let stats = svc.cache_stats().unwrap();
+        assert!(stats.hits > 0);
+        assert_eq!(stats.evictions, 0);
+
+        let mut parameters = Parameters::default();
+        parameters.with_cache_size(256);
+        let mut small = SVC::new(parameters);
+        small.fit(&x, &y).unwrap();
+        assert!(small.cache_stats().unwrap().evictions > 0);
+        assert_eq!(small.predict(&x).unwrap(), svc.predict(&x).unwrap());
+    }
+
+    #[test]
+    fn svc_support_attributes() {
+        let (x, y) = blobs([7, 3]);
+        let mut svc = SVC::new(Parameters::default());
+        svc.fit(&x, &y).unwrap();
+
+        let support = svc.support_().unwrap();
+        let dual_coef = svc.dual_coef_().unwrap();
+        let labels = svc.support_labels_().unwrap();
+        assert_eq!(support.len(), dual_coef.len());
+        assert_eq!(
+            labels,
+            support.iter().map(|&i| y[i]).collect::<Vec<_>>().as_slice()
+        );
+        let n_support = svc.n_support_().unwrap();
+        assert_eq!(n_support[0], labels.iter().filter(|&&l| l == 3).count());
+        assert_eq!(n_support[0] + n_support[1], support.len());
+        for (coef, label) in dual_coef.iter().zip(labels) {
+            assert_eq!(*coef > 0.0, *label == 7);
+            assert!(coef.abs() <= 1.0 + 1e-9);
+        }
+        assert!(dual_coef.iter().sum::<f64>().abs() < 1e-9);
+
+        let json = serde_json::to_string(&svc).unwrap();
+        let loaded: SVC = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.support_(), Some(support));
+        assert_eq!(loaded.n_support_(), Some(n_support));
+        assert_eq!(loaded.support_labels_(), Some(labels));
+    }
+
+    #[test]
+    fn svc_save_load() {
+        let x = vec![
+            vec![1.0, 1.0],
+            vec![1.5, 2.0],
+            vec![2.0, 1.5],
+            vec![-1.0, -1.0],
+            vec![-1.5, -2.0],
+            vec![-2.0, -1.5],
+        ];
+        let y = vec![1, 1, 1, 0, 0, 0];
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(Box::new(RBFKernel::new(0.3)));
+        let mut svc = SVC::new(parameters);
+        svc.fit(&x, &y).unwrap();
+
+        let file = TempFile::new("svc_save_load.json");
+        svc.save(file.path()).unwrap();
+        let loaded = SVC::<f64>::load(file.path()).unwrap();
+        assert_eq!(
+            loaded.decision_function(&x).unwrap(),
+            svc.decision_function(&x).unwrap()
+        );
+        assert_eq!(loaded.classes(), svc.classes());
+
+        let mut buffer = Vec::new();
+        svc.save_to_writer(&mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        assert!(text.starts_with("{\"format\":\"svm-burns\""));
+
+        let other_kernel = text.replacen("{\"RBF\":0.3}", "\"Linear\"", 1);
+        assert!(matches!(
+            SVC::<f64>::load_from_reader(other_kernel.as_bytes()),
+            Err(Error::Format(message)) if message.contains("kernel")
+        ));
+        let corrupted = text.replace(
+            "\"n_features\":2,\"checksum\"",
+            "\"n_features\":3,\"checksum\"",
+        );
+        assert!(matches!(
+            SVC::<f64>::load_from_reader(corrupted.as_bytes()),
+            Err(Error::Format(message)) if message.contains("features")
+        ));
+        assert!(matches!(
+            SVC::<f64>::load_from_reader("[1, 2]".as_bytes()),
+            Err(Error::Format(_))
+        ));
+        let missing = TempFile::new("svc_missing.json");
+        assert!(matches!(
+            SVC::<f64>::load(missing.path()),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn svc_save_load_binary() {
+        let x: Vec<Vec<f32>> = (0..40)
+            .map(|i| {
+                let t = i as f32 / 7.0;
+                vec![t.sin() + (i % 2) as f32, t.cos(), 0.1 * t]
+            })
+            .collect();
+        let y: Vec<i32> = (0..40).map(|i| i % 2).collect();
+        let mut parameters = Parameters::<f32>::new(Box::new(RBFKernel::new(0.7)), 1.0, 1e-3, 2);
+        parameters.with_probability(true);
+        let mut svc = SVC::new(parameters);
+        svc.fit(&x, &y).unwrap();
+
+        let mut buffer = Vec::new();
+        svc.save_binary_to_writer(&mut buffer).unwrap();
+        let loaded = SVC::<f32>::load_binary_from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(
+            serde_json::to_string(&loaded).unwrap(),
+            serde_json::to_string(&svc).unwrap()
+        );
+        assert!(buffer.len() < serde_json::to_vec(&svc).unwrap().len());
+
+        let mut sparse = SVC::new(Parameters::<f32>::new(
+            Box::new(LinearKernel::default()),
+            1.0,
+            1e-3,
+            2,
+        ));
+        sparse.fit_sparse(&CsrMatrix::from_dense(&x), &y).unwrap();
+        let file = TempFile::new("svc_binary.bin");
+        sparse.save_binary(file.path()).unwrap();
+        let loaded = SVC::<f32>::load_binary(file.path()).unwrap();
+        assert_eq!(
+            serde_json::to_string(&loaded).unwrap(),
+            serde_json::to_string(&sparse).unwrap()
+        );
+
+        assert!(matches!(
+            SVC::<f32>::load_binary_from_reader(&buffer[..buffer.len() - 3]),
+            Err(Error::Format(message)) if message.contains("end")
+        ));
+        // the last byte of the KKT violation before the checksum
+        let mut corrupted = buffer.clone();
+        let index = corrupted.len() - 9;
+        corrupted[index] ^= 0x01;
+        assert!(matches!(
+            SVC::<f32>::load_binary_from_reader(corrupted.as_slice()),
+            Err(Error::Format(message)) if message.contains("checksum")
+        ));
+        assert!(matches!(
+            SVC::<f64>::load_binary_from_reader(buffer.as_slice()),
+            Err(Error::Format(message)) if message.contains("32 bit")
+        ));
+        assert!(matches!(
+            SVC::<f32>::load_binary_from_reader("{}".as_bytes()),
+            Err(Error::Format(_))
+        ));
+    }
+
+    #[test]
+    fn svc_libsvm_model() {
+        let (x, y) = blobs([2, 5]);
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(Box::new(RBFKernel::new(0.5)));
+        let mut svc = SVC::new(parameters);
+        svc.fit(&x, &y).unwrap();
+
+        let model = svc.to_libsvm_model().unwrap();
+        assert_eq!(model.labels, [5, 2]);
+        assert!(model.coef[..model.n_sv[0]].iter().all(|&coef| coef > 0.0));
+        let mut text = Vec::new();
+        model.write(&mut text).unwrap();
+        let imported =
+            SVC::from_libsvm_model(LibsvmModel::read(text.as_slice()).unwrap(), 2).unwrap();
+        assert_eq!(imported.classes(), Some(&[2, 5][..]));
+        assert_eq!(imported.predict(&x).unwrap(), svc.predict(&x).unwrap());
+        for (a, b) in imported
+            .decision_function(&x)
+            .unwrap()
+            .iter()
+            .zip(svc.decision_function(&x).unwrap())
+        {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // f(x) = 2 <(1, 0, 3), x> - <(0, 1, 0), x> + 0.5 is positive for the first label 1
+        let linear = "svm_type c_svc\nkernel_type linear\nnr_class 2\ntotal_sv 2\nrho -0.5\n\
+            label 1 -1\nnr_sv 1 1\nSV\n2 1:1 3:3\n-1 2:1\n";
+        let file = TempFile::new("svc_libsvm.model");
+        std::fs::write(file.path(), linear).unwrap();
+        let imported = SVC::<f64>::load_libsvm_model(file.path(), 3).unwrap();
+        assert_eq!(imported.coef_(), Some(&[2.0, -1.0, 6.0][..]));
+        assert_eq!(
+            imported
+                .decision_function(&vec![vec![1.0, 1.0, 1.0]])
+                .unwrap(),
+            vec![2.0 * 4.0 - 1.0 + 0.5]
+        );
+        assert_eq!(
+            imported.predict(&vec![vec![1.0, 1.0, 1.0]]).unwrap(),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn svc_libsvm_model_n_features() {
+        // the third feature is zero in every support vector but not in the samples
+        let text = "svm_type c_svc\nkernel_type rbf\ngamma 0.5\nnr_class 2\ntotal_sv 2\n\
+            rho 0\nlabel 1 -1\nnr_sv 1 1\nSV\n1 1:1\n-1 2:1\n";
+        let model = LibsvmModel::<f64>::read(text.as_bytes()).unwrap();
+        assert!(matches!(
+            SVC::from_libsvm_model(model.clone(), 1),
+            Err(Error::DimensionMismatch(_))
+        ));
+
+        let svc = SVC::from_libsvm_model(model, 3).unwrap();
+        let values = svc.decision_function(&[vec![1.0, 0.0, 2.0]]).unwrap();
+        assert!((values[0] - ((-2.0f64).exp() - (-3.0f64).exp())).abs() < 1e-12);
+        assert!(matches!(
+            svc.predict(&[vec![1.0, 0.0]]),
+            Err(Error::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn svc_step_inside_box() {
+        // alpha = 0.5 is inside the box [0, 10], a step clipped to the bound would give
+        // alpha = 10 and f(x) = 20 x
+        let mut parameters = Parameters::default();
+        parameters.with_kernel(KernelType::linear()).with_c(10.0);
+        let mut svc = SVC::new(parameters);
+        let x = vec![vec![1.0], vec![-1.0]];
+        svc.fit(&x, &[1, -1]).unwrap();
+
+        let values = svc.decision_function(&x).unwrap();
+        assert!((values[0] - 1.0).abs() < 1e-6);
+        assert!((values[1] + 1.0).abs() < 1e-6);
+    }
 }
@@ -1,4 +1,12 @@
-pub trait SVM {
-    fn fit(&mut self, x: &Vec<Vec<f64>>, y: &Vec<i32>);
-    fn predict(&self, x: &Vec<Vec<f64>>) -> Vec<i32>;
+use crate::{error::Result, float::Float, matrix::Matrix};
+
+pub trait SVM<T: Float = f64> {
+    fn fit(&mut self, x: &(impl Matrix<T> + ?Sized), y: &[i32]) -> Result<()>;
+    fn predict(&self, x: &(impl Matrix<T> + ?Sized)) -> Result<Vec<i32>>;
+}
+
+/// Support vector machine for regression on continuous targets.
+pub trait SVMRegressor {
+    fn fit(&mut self, x: &(impl Matrix + ?Sized), y: &[f64]) -> Result<()>;
+    fn predict(&self, x: &(impl Matrix + ?Sized)) -> Result<Vec<f64>>;
 }
@@ -0,0 +1,313 @@
+// source: https://www.csie.ntu.edu.tw/~cjlin/papers/libsvm.pdf
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    kernel::{FeatureKernelSource, KernelSource, PrecomputedKernelSource},
+    matrix::Matrix,
+    optimizer::{Convergence, ProgressHook},
+    parameters::Parameters,
+    smartcore_optimizer::SMO,
+    svc::SVC,
+    svm::SVMRegressor,
+    validation, B, W,
+};
+
+/// Epsilon support vector regression.
+///
+/// The dual problem has the variables `alpha_i^+` and `alpha_i^-` per sample. Both are
+/// solved as one problem of 2n variables by the SMO optimizer, the first n with bounds
+/// `[0, c]` and target `y_i - epsilon`, the last n with bounds `[-c, 0]` and target
+/// `y_i + epsilon`.
+#[derive(Serialize, Deserialize)]
+pub struct SVR {
+    parameters: Parameters,
+    support_vectors: Option<Vec<Vec<f64>>>,
+    /// training indices of the support vectors
+    support: Option<Vec<usize>>,
+    w: Option<W>,
+    b: Option<B>,
+    /// number of features, or of training samples with a precomputed kernel
+    n_features: Option<usize>,
+    /// how the optimizer stopped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    convergence: Option<Convergence>,
+    #[serde(skip)]
+    progress: Option<ProgressHook>,
+}
+
+unsafe impl Sync for SVR {}
+unsafe impl Send for SVR {}
+
+/// Kernel source over the 2n variables of the regression problem.
+struct DoubledKernelSource<'a> {
+    source: &'a dyn KernelSource,
+}
+
+impl KernelSource for DoubledKernelSource<'_> {
+    fn n_samples(&self) -> usize {
+        2 * self.source.n_samples()
+    }
+
+    fn compute(&self, i: usize, j: usize) -> f64 {
+        let n = self.source.n_samples();
+        self.source.compute(i % n, j % n)
+    }
+}
+
+impl SVR {
+    pub fn new(parameters: Parameters) -> SVR {
+        SVR {
+            parameters,
+            support_vectors: None,
+            support: None,
+            w: None,
+            b: None,
+            n_features: None,
+            convergence: None,
+            progress: None,
+        }
+    }
+
+    fn is_precomputed(&self) -> bool {
+        self.parameters.precomputed
+    }
+
+    /// Reports the training progress to `progress`, the hook can cancel the training.
+    ///
+    /// A cancelled model keeps the solution found so far.
+    pub fn with_progress(&mut self, progress: ProgressHook) -> &mut Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// How the optimizer stopped during training.
+    pub fn convergence(&self) -> Option<Convergence> {
+        self.convergence
+    }
+
+    /// Solves the doubled problem, `progress` is handed to the optimizer and returned
+    /// afterwards.
+    fn optimize(
+        parameters: &Parameters,
+        progress: &mut Option<ProgressHook>,
+        source: &dyn KernelSource,
+        y: &[f64],
+    ) -> ((Vec<usize>, Vec<f64>, f64), Option<Convergence>) {
+        let c = parameters.c;
+        let epsilon = parameters.epsilon;
+        let targets: Vec<f64> = y
+            .iter()
+            .map(|y_i| y_i - epsilon)
+            .chain(y.iter().map(|y_i| y_i + epsilon))
+            .collect();
+        let bounds: Vec<(f64, f64)> = std::iter::repeat_n((0.0, c), y.len())
+            .chain(std::iter::repeat_n((-c, 0.0), y.len()))
+            .collect();
+
+        let mut smo = SMO::new(c, parameters.tol, parameters.epochs);
+        smo.with_seed(Some(100))
+            .with_max_iter(parameters.max_iter)
+            .with_max_time(parameters.max_time)
+            .with_cache_size(parameters.cache_size)
+            .with_progress(progress.take());
+        let (variables, alphas, b) =
+            smo.optimize_problem(&DoubledKernelSource { source }, &targets, &bounds);
+        *progress = smo.take_progress();
+
+        // alpha_i^+ - alpha_i^- per sample
+        let mut coefficients = vec![0.0; y.len()];
+        for (variable, alpha) in variables.iter().zip(alphas.iter()) {
+            coefficients[variable % y.len()] += alpha;
+        }
+        let (support, w) = coefficients
+            .into_iter()
+            .enumerate()
+            .filter(|(_, w_i)| *w_i != 0.0)
+            .unzip();
+        ((support, w, b), smo.convergence())
+    }
+
+    /// Computes the predicted values of the samples in `x`.
+    ///
+    /// With a precomputed kernel `x` is the m×n kernel matrix between the m test samples
+    /// and the n training samples.
+    pub fn decision_function(&self, x: &(impl Matrix + ?Sized)) -> Result<Vec<f64>> {
+        let x: &dyn Matrix = &x;
+        let w = self.w.as_ref().ok_or(Error::NotFitted)?;
+        let b = self.b.ok_or(Error::NotFitted)?;
+        validation::check_predict_input(x, self.n_features)?;
+
+        if self.is_precomputed() {
+            let support = self.support.as_ref().ok_or(Error::NotFitted)?;
+            return Ok((0..x.n_rows())
+                .map(|i| SVC::predict_precomputed_row(x.row(i), w, support, b))
+                .collect());
+        }
+
+        let support_vectors = self.support_vectors.as_ref().ok_or(Error::NotFitted)?;
+
+        #[cfg(feature = "parallel")]
+        let y: Vec<f64> = (0..x.n_rows())
+            .into_par_iter()
+            .map(|i| SVC::predict_row(x.row(i), w, support_vectors, b, &self.parameters.kernel))
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let y: Vec<f64> = (0..x.n_rows())
+            .map(|i| SVC::predict_row(x.row(i), w, support_vectors, b, &self.parameters.kernel))
+            .collect();
+        Ok(y)
+    }
+}
+
+impl SVMRegressor for SVR {
+    /// Trains the model. With a precomputed kernel `x` is the n×n Gram matrix of the
+    /// training samples.
+    fn fit(&mut self, x: &(impl Matrix + ?Sized), y: &[f64]) -> Result<()> {
+        let x: &dyn Matrix = &x;
+        let precomputed = self.is_precomputed();
+        let n_features = validation::check_fit_input(x, Some(y.len()), precomputed)?;
+        validation::check_c(self.parameters.c)?;
+        validation::check_epsilon(self.parameters.epsilon)?;
+
+        let source: Box<dyn KernelSource> = if precomputed {
+            Box::new(PrecomputedKernelSource::new(x))
+        } else {
+            Box::new(FeatureKernelSource::new(x, &self.parameters.kernel))
+        };
+        let ((support, w, b), convergence) =
+            Self::optimize(&self.parameters, &mut self.progress, source.as_ref(), y);
+
+        self.support_vectors = if precomputed {
+            None
+        } else {
+            Some(support.iter().map(|&i| x.row(i).to_vec()).collect())
+        };
+        self.support = Some(support);
+        self.w = Some(w);
+        self.b = Some(b);
+        self.n_features = Some(n_features);
+        self.convergence = convergence;
+        Ok(())
+    }
+
+    fn predict(&self, x: &(impl Matrix + ?Sized)) -> Result<Vec<f64>> {
+        self.decision_function(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        kernel::RBFKernel,
+        optimizer::{ConvergenceStatus, Progress, Signal},
+    };
+
+    fn sine() -> (Vec<Vec<f64>>, Vec<f64>) {
+        let x: Vec<Vec<f64>> = (0..40).map(|i| vec![i as f64 * 0.15]).collect();
+        let y = x.iter().map(|x_i| x_i[0].sin()).collect();
+        (x, y)
+    }
+
+    #[test]
+    fn svr_fit_predict_rbf() {
+        let (x, y) = sine();
+
+        let mut parameters = Parameters::default();
+        parameters
+            .with_kernel(Box::new(RBFKernel::new(1.0)))
+            .with_c(10.0)
+            .with_epsilon(0.05);
+        let mut svr = SVR::new(parameters);
+
+        svr.fit(&x, &y).unwrap();
+
+        let predictions = svr.predict(&x).unwrap();
+        let max_error = predictions
+            .iter()
+            .zip(y.iter())
+            .map(|(prediction, y_i)| (prediction - y_i).abs())
+            .fold(0.0, f64::max);
+
+        assert!(
+            max_error < 0.2,
+            "Maximum error ({max_error}) is not below 0.2"
+        );
+    }
+
+    #[test]
+    fn svr_serialize() {
+        let x: Vec<Vec<f64>> = (0..10).map(|i| vec![i as f64]).collect();
+        let y: Vec<f64> = x.iter().map(|x_i| 0.5 * x_i[0]).collect();
+
+        let mut svr = SVR::new(Parameters::default());
+        svr.fit(&x, &y).unwrap();
+
+        let json = serde_json::to_string(&svr).unwrap();
+        let loaded: SVR = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.predict(&x).unwrap(), svr.predict(&x).unwrap());
+    }
+
+    #[test]
+    fn svr_convergence() {
+        let (x, y) = sine();
+
+        let mut svr = SVR::new(Parameters::default());
+        svr.fit(&x, &y).unwrap();
+        let convergence = svr.convergence().unwrap();
+        assert_eq!(convergence.status, ConvergenceStatus::Converged);
+        assert!(convergence.iterations > 0);
+        assert!(convergence.kkt_violation <= 1e-3);
+
+        let mut parameters = Parameters::default();
+        parameters.with_max_iter(1);
+        let mut svr = SVR::new(parameters);
+        svr.fit(&x, &y).unwrap();
+        let convergence = svr.convergence().unwrap();
+        assert_eq!(convergence.status, ConvergenceStatus::IterationLimit);
+        assert_eq!(convergence.iterations, 1);
+
+        let mut parameters = Parameters::default();
+        parameters.with_max_time(Duration::ZERO);
+        let mut svr = SVR::new(parameters);
+        svr.fit(&x, &y).unwrap();
+        assert_eq!(
+            svr.convergence().unwrap().status,
+            ConvergenceStatus::TimeLimit
+        );
+        assert_eq!(svr.predict(&x).unwrap().len(), x.len());
+    }
+
+    #[test]
+    fn svr_progress() {
+        let (x, y) = sine();
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::<Progress>::new()));
+        let seen = reports.clone();
+        let mut svr = SVR::new(Parameters::default());
+        svr.with_progress(ProgressHook::new(2, move |progress| {
+            seen.lock().unwrap().push(*progress);
+            if progress.iterations >= 4 {
+                Signal::Cancel
+            } else {
+                Signal::Continue
+            }
+        }));
+        svr.fit(&x, &y).unwrap();
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(
+            reports.iter().map(|p| p.iterations).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        let convergence = svr.convergence().unwrap();
+        assert_eq!(convergence.status, ConvergenceStatus::Cancelled);
+        assert_eq!(convergence.iterations, 4);
+        assert_eq!(svr.predict(&x).unwrap().len(), x.len());
+    }
+}
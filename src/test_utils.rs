@@ -0,0 +1,98 @@
+//! Fixtures shared by the unit tests.
+
+use std::path::{Path, PathBuf};
+
+/// 40 samples on a grid, labeled by the line `x_0 + x_1 = 1`.
+pub(crate) fn grid() -> (Vec<Vec<f64>>, Vec<i32>) {
+    let x: Vec<Vec<f64>> = (0..40)
+        .map(|i| vec![(i % 7) as f64 * 0.3, (i % 5) as f64 * 0.2])
+        .collect();
+    let y = x
+        .iter()
+        .map(|x_i| if x_i[0] + x_i[1] > 1.0 { 1 } else { -1 })
+        .collect();
+    (x, y)
+}
+
+/// Two blobs of four samples each, the last sample of each blob lies near the origin.
+pub(crate) fn blobs(labels: [i32; 2]) -> (Vec<Vec<f64>>, Vec<i32>) {
+    let x = vec![
+        vec![1.0, 1.0],
+        vec![1.5, 2.0],
+        vec![2.0, 1.5],
+        vec![0.2, 0.1],
+        vec![-1.0, -1.0],
+        vec![-1.5, -2.0],
+        vec![-2.0, -1.5],
+        vec![0.1, 0.3],
+    ];
+    let y = labels.iter().flat_map(|&label| [label; 4]).collect();
+    (x, y)
+}
+
+/// The first ten samples of each species of the iris dataset, labeled 0, 1 and 2.
+pub(crate) fn iris() -> (Vec<Vec<f64>>, Vec<i32>) {
+    let x = vec![
+        vec![5.1, 3.5, 1.4, 0.2],
+        vec![4.9, 3.0, 1.4, 0.2],
+        vec![4.7, 3.2, 1.3, 0.2],
+        vec![4.6, 3.1, 1.5, 0.2],
+        vec![5.0, 3.6, 1.4, 0.2],
+        vec![5.4, 3.9, 1.7, 0.4],
+        vec![4.6, 3.4, 1.4, 0.3],
+        vec![5.0, 3.4, 1.5, 0.2],
+        vec![4.4, 2.9, 1.4, 0.2],
+        vec![4.9, 3.1, 1.5, 0.1],
+        vec![7.0, 3.2, 4.7, 1.4],
+        vec![6.4, 3.2, 4.5, 1.5],
+        vec![6.9, 3.1, 4.9, 1.5],
+        vec![5.5, 2.3, 4.0, 1.3],
+        vec![6.5, 2.8, 4.6, 1.5],
+        vec![5.7, 2.8, 4.5, 1.3],
+        vec![6.3, 3.3, 4.7, 1.6],
+        vec![4.9, 2.4, 3.3, 1.0],
+        vec![6.6, 2.9, 4.6, 1.3],
+        vec![5.2, 2.7, 3.9, 1.4],
+        vec![6.3, 3.3, 6.0, 2.5],
+        vec![5.8, 2.7, 5.1, 1.9],
+        vec![7.1, 3.0, 5.9, 2.1],
+        vec![6.3, 2.9, 5.6, 1.8],
+        vec![6.5, 3.0, 5.8, 2.2],
+        vec![7.6, 3.0, 6.6, 2.1],
+        vec![4.9, 2.5, 4.5, 1.7],
+        vec![7.3, 2.9, 6.3, 1.8],
+        vec![6.7, 2.5, 5.8, 1.8],
+        vec![7.2, 3.6, 6.1, 2.5],
+    ];
+    let y = (0..3).flat_map(|label| [label; 10]).collect();
+    (x, y)
+}
+
+/// The setosa and versicolor samples of [`iris`], labeled -1 and 1.
+pub(crate) fn iris_binary() -> (Vec<Vec<f64>>, Vec<i32>) {
+    let (mut x, _) = iris();
+    x.truncate(20);
+    let y = (0..20).map(|i| if i < 10 { -1 } else { 1 }).collect();
+    (x, y)
+}
+
+/// Path in the temporary directory that is removed when the guard is dropped.
+pub(crate) struct TempFile(PathBuf);
+
+impl TempFile {
+    /// The file name is prefixed with the process id, so parallel test runs do not collide.
+    pub(crate) fn new(name: &str) -> Self {
+        TempFile(std::env::temp_dir().join(format!("svm_burns_{}_{name}", std::process::id())))
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // the file may not have been written
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
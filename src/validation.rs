@@ -0,0 +1,171 @@
+//! Input checks shared by the estimators.
+
+use crate::{
+    error::{Error, Result},
+    float::Float,
+    matrix::Matrix,
+};
+
+/// Checks the training samples and returns their number of features.
+///
+/// With a precomputed kernel `x` has to be a square kernel matrix and the number of
+/// features is the number of training samples.
+pub(crate) fn check_fit_input<T: Float>(
+    x: &dyn Matrix<T>,
+    n_targets: Option<usize>,
+    precomputed: bool,
+) -> Result<usize> {
+    let n_rows = x.n_rows();
+    check_n_samples(n_rows, n_targets)?;
+    let n_features = if precomputed { n_rows } else { x.n_cols() };
+    if let Some(row) = (0..n_rows).find(|&i| x.row(i).len() != n_features) {
+        return Err(Error::DimensionMismatch(if precomputed {
+            format!(
+                "precomputed kernel matrix must be square, row {row} has {} columns but there are {n_rows} rows",
+                x.row(row).len()
+            )
+        } else {
+            format!(
+                "row {row} has {} features but row 0 has {n_features}",
+                x.row(row).len()
+            )
+        }));
+    }
+    Ok(n_features)
+}
+
+/// Checks that there are samples and as many as targets.
+pub(crate) fn check_n_samples(n_samples: usize, n_targets: Option<usize>) -> Result<()> {
+    if n_samples == 0 {
+        return Err(Error::EmptyInput);
+    }
+    match n_targets {
+        Some(n_targets) if n_targets != n_samples => Err(Error::DimensionMismatch(format!(
+            "{n_samples} samples in x but {n_targets} targets in y"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that every sample has the number of features seen during training.
+pub(crate) fn check_predict_input<T: Float>(
+    x: &dyn Matrix<T>,
+    n_features: Option<usize>,
+) -> Result<()> {
+    let Some(n_features) = n_features else {
+        return Ok(());
+    };
+    match (0..x.n_rows()).find(|&i| x.row(i).len() != n_features) {
+        Some(row) => Err(Error::DimensionMismatch(format!(
+            "row {row} has {} features but the model was trained with {n_features}",
+            x.row(row).len()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Checks that `nu` is in `(0, 1]`.
+pub(crate) fn check_nu(nu: f64) -> Result<()> {
+    if nu > 0.0 && nu <= 1.0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!(
+            "nu must be in (0, 1], got {nu}"
+        )))
+    }
+}
+
+/// Checks that `c` is positive.
+pub(crate) fn check_c(c: f64) -> Result<()> {
+    if c > 0.0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!(
+            "c must be positive, got {c}"
+        )))
+    }
+}
+
+/// Checks that `epsilon` is not negative.
+pub(crate) fn check_epsilon(epsilon: f64) -> Result<()> {
+    if epsilon >= 0.0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!(
+            "epsilon must not be negative, got {epsilon}"
+        )))
+    }
+}
+
+/// Checks that there is one positive weight per sample.
+pub(crate) fn check_sample_weight(sample_weight: &[f64], n_samples: usize) -> Result<()> {
+    if sample_weight.len() != n_samples {
+        return Err(Error::DimensionMismatch(format!(
+            "{n_samples} samples but {} sample weights",
+            sample_weight.len()
+        )));
+    }
+    match sample_weight
+        .iter()
+        .find(|&&weight| !(weight > 0.0 && weight.is_finite()))
+    {
+        Some(weight) => Err(Error::InvalidParameter(format!(
+            "weights must be positive and finite, got {weight}"
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_input() {
+        let x = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+        assert_eq!(check_fit_input(&x, Some(2), false).unwrap(), 2);
+        assert!(matches!(
+            check_fit_input(&x, Some(3), false),
+            Err(Error::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            check_fit_input(&Vec::<Vec<f64>>::new(), None, false),
+            Err(Error::EmptyInput)
+        ));
+
+        let ragged = vec![vec![1.0, 2.0], vec![3.0]];
+        assert!(matches!(
+            check_fit_input(&ragged, None, false),
+            Err(Error::DimensionMismatch(_))
+        ));
+        let not_square = vec![vec![1.0, 2.0, 3.0], vec![3.0, 4.0, 5.0]];
+        assert!(matches!(
+            check_fit_input(&not_square, None, true),
+            Err(Error::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn predict_input() {
+        let x = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+        assert!(check_predict_input(&x, Some(2)).is_ok());
+        assert!(check_predict_input(&x, None).is_ok());
+        assert!(matches!(
+            check_predict_input(&x, Some(3)),
+            Err(Error::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn parameter_ranges() {
+        assert!(check_nu(0.5).is_ok());
+        assert!(check_nu(1.0).is_ok());
+        assert!(check_nu(0.0).is_err());
+        assert!(check_nu(1.5).is_err());
+        assert!(check_c(1.0).is_ok());
+        assert!(check_c(0.0).is_err());
+        assert!(check_c(f64::NAN).is_err());
+        assert!(check_epsilon(0.0).is_ok());
+        assert!(check_epsilon(-0.1).is_err());
+    }
+}